@@ -0,0 +1,563 @@
+//! A synchronous client for tools that do not run their own tokio runtime.
+//!
+//! Devices come wrapped in a `Device`, which offers the operations of the async client
+//! without an `X1` argument:
+//!
+//! ```no_run
+//! let x1 = gira_iot_api::blocking::X1::new("10.10.1.12", "Username", "Password");
+//! x1.connect().expect("Error connecting to the X1");
+//! let mut light = x1.lights().pop().expect("no lights");
+//! light.switch_on().expect("Error switching on");
+//! ```
+//!
+//! Anything not wrapped here can still be run to completion with `X1::run`.
+//! Do not use it from within an async context; blocking there panics.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+
+use crate::cameras::{Camera, SnapshotError};
+use crate::climate::{FanCoil, Thermostat};
+use crate::confirm::{ConfirmOptions, Confirmation};
+use crate::covers::Blind;
+use crate::function::X1Function;
+use crate::generic::{DataPointError, DataValue, GenericFunction};
+use crate::groups::GroupResult;
+use crate::lights::Light;
+use crate::links::Link;
+use crate::locations::{Location, LocationId, LocationNode};
+use crate::media::AudioPlayer;
+use crate::queue::WriteOutcome;
+use crate::scenes::Scene;
+use crate::search::{Candidate, FindError, Query};
+use crate::sensors::{BinarySensor, NumericSensor, TextSensor};
+use crate::trades::Trade;
+use crate::triggers::{PressAndHold, Trigger};
+use crate::x1;
+
+#[derive(Clone, Debug)]
+pub struct X1 {
+    inner: x1::X1,
+    runtime: Arc<Runtime>,
+}
+
+impl X1 {
+    pub fn new(addr: &str, user: &str, password: &str) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("Error creating runtime");
+        X1 {
+            inner: x1::X1::new(addr, user, password),
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// The async client this one is built on.
+    pub fn inner(&self) -> &x1::X1 {
+        &self.inner
+    }
+
+    /// Runs an operation of the async client to completion, e.g.
+    /// `x1.run(|x1| light.dimm(x1, 50))`.
+    pub fn run<'a, F, Fut>(&'a self, operation: F) -> Fut::Output
+    where
+        F: FnOnce(&'a x1::X1) -> Fut,
+        Fut: Future,
+    {
+        self.runtime.block_on(operation(&self.inner))
+    }
+
+    pub fn connect(&self) -> Result<(), Arc<reqwest::Error>> {
+        self.run(|x1| x1.connect())
+    }
+
+    pub fn refresh(&self) -> Result<(), reqwest::Error> {
+        self.run(|x1| x1.refresh())
+    }
+
+    pub fn refresh_states(&self) -> Result<(), reqwest::Error> {
+        self.run(|x1| x1.refresh_states())
+    }
+
+    pub fn get_value(&self, uid: String) -> Result<u16, reqwest::Error> {
+        self.run(|x1| x1.get_value(uid))
+    }
+
+    pub fn get_fn_values(&self, uid: String) -> Result<HashMap<String, u16>, reqwest::Error> {
+        self.run(|x1| x1.get_fn_values(uid))
+    }
+
+    pub fn get_fn_raw_values(
+        &self,
+        uid: String,
+    ) -> Result<HashMap<String, String>, reqwest::Error> {
+        self.run(|x1| x1.get_fn_raw_values(uid))
+    }
+
+    pub fn set_value(&self, uid: String, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|x1| x1.set_value(uid, value))
+    }
+
+    pub fn set_values(&self, values: Vec<(String, u16)>) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|x1| x1.set_values(values))
+    }
+
+    pub fn set_raw_value(
+        &self,
+        uid: String,
+        value: String,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|x1| x1.set_raw_value(uid, value))
+    }
+
+    pub fn set_value_confirmed(
+        &self,
+        uid: String,
+        value: u16,
+        options: &ConfirmOptions,
+    ) -> Result<Confirmation, reqwest::Error> {
+        self.run(|x1| x1.set_value_confirmed(uid, value, options))
+    }
+
+    pub fn set_raw_value_confirmed(
+        &self,
+        uid: String,
+        value: String,
+        options: &ConfirmOptions,
+    ) -> Result<Confirmation, reqwest::Error> {
+        self.run(|x1| x1.set_raw_value_confirmed(uid, value, options))
+    }
+
+    pub fn search(&self, query: &Query) -> Vec<Candidate> {
+        self.run(|x1| x1.search(query))
+    }
+
+    pub fn find(&self, query: &Query) -> Result<X1Function, FindError> {
+        self.run(|x1| x1.find(query))
+    }
+
+    pub fn add_alias(&self, alias: &str, uid: &str) {
+        self.run(|x1| x1.add_alias(alias, uid))
+    }
+
+    pub fn function(&self, uid: &str) -> Option<X1Function> {
+        self.run(|x1| x1.functions.get(uid))
+    }
+
+    pub fn functions_in_trade(&self, trade: &str) -> Vec<X1Function> {
+        self.run(|x1| x1.functions_in_trade(trade))
+    }
+
+    pub fn functions_in(&self, trade: &str, location: LocationId) -> Vec<X1Function> {
+        self.run(|x1| x1.functions_in(trade, location))
+    }
+
+    pub fn functions_in_location(&self, location: LocationId, recursive: bool) -> Vec<X1Function> {
+        self.run(|x1| x1.functions_in_location(location, recursive))
+    }
+
+    pub fn switch_on_lights_in(&self, location: LocationId, recursive: bool) -> GroupResult {
+        self.run(|x1| x1.switch_on_lights_in(location, recursive))
+    }
+
+    pub fn switch_off_lights_in(&self, location: LocationId, recursive: bool) -> GroupResult {
+        self.run(|x1| x1.switch_off_lights_in(location, recursive))
+    }
+
+    pub fn dim_lights_in(
+        &self,
+        location: LocationId,
+        recursive: bool,
+        percent: u16,
+    ) -> GroupResult {
+        self.run(|x1| x1.dim_lights_in(location, recursive, percent))
+    }
+
+    pub fn set_blinds_in(
+        &self,
+        location: LocationId,
+        recursive: bool,
+        position: u16,
+    ) -> GroupResult {
+        self.run(|x1| x1.set_blinds_in(location, recursive, position))
+    }
+
+    pub fn locations(&self) -> Locations<'_> {
+        Locations { x1: self }
+    }
+
+    pub fn trades(&self) -> Trades<'_> {
+        Trades { x1: self }
+    }
+
+    fn devices<T>(&self, devices: Vec<T>) -> Vec<Device<T>> {
+        devices
+            .into_iter()
+            .map(|device| Device {
+                x1: self.clone(),
+                device,
+            })
+            .collect()
+    }
+
+    pub fn lights(&self) -> Vec<Device<Light>> {
+        self.devices(self.run(|x1| x1.lights.get_all()))
+    }
+
+    pub fn blinds(&self) -> Vec<Device<Blind>> {
+        self.devices(self.run(|x1| x1.blinds.get_all()))
+    }
+
+    pub fn thermostats(&self) -> Vec<Device<Thermostat>> {
+        self.devices(self.run(|x1| x1.thermostats.get_all()))
+    }
+
+    pub fn fan_coils(&self) -> Vec<Device<FanCoil>> {
+        self.devices(self.run(|x1| x1.fan_coils.get_all()))
+    }
+
+    pub fn scenes(&self) -> Vec<Device<Scene>> {
+        self.devices(self.run(|x1| x1.scenes.get_all()))
+    }
+
+    pub fn triggers(&self) -> Vec<Device<Trigger>> {
+        self.devices(self.run(|x1| x1.triggers.get_all()))
+    }
+
+    pub fn press_and_holds(&self) -> Vec<Device<PressAndHold>> {
+        self.devices(self.run(|x1| x1.press_and_holds.get_all()))
+    }
+
+    pub fn audio_players(&self) -> Vec<Device<AudioPlayer>> {
+        self.devices(self.run(|x1| x1.audio_players.get_all()))
+    }
+
+    pub fn cameras(&self) -> Vec<Device<Camera>> {
+        self.devices(self.run(|x1| x1.cameras.get_all()))
+    }
+
+    pub fn generic_functions(&self) -> Vec<Device<GenericFunction>> {
+        let functions = self.run(|x1| x1.functions.get_all());
+        self.devices(
+            functions
+                .into_iter()
+                .filter_map(|function| match function {
+                    X1Function::GENERIC(function) => Some(function),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn links(&self) -> Vec<Link> {
+        self.run(|x1| x1.links.get_all())
+    }
+
+    pub fn binary_sensors(&self) -> Vec<BinarySensor> {
+        self.run(|x1| x1.sensors.get_binary())
+    }
+
+    pub fn numeric_sensors(&self) -> Vec<NumericSensor> {
+        self.run(|x1| x1.sensors.get_numeric())
+    }
+
+    pub fn text_sensors(&self) -> Vec<TextSensor> {
+        self.run(|x1| x1.sensors.get_text())
+    }
+
+    pub fn binary_sensors_in(&self, location: LocationId) -> Vec<BinarySensor> {
+        self.run(|x1| x1.sensors.get_binary_in(location))
+    }
+
+    pub fn numeric_sensors_in(&self, location: LocationId) -> Vec<NumericSensor> {
+        self.run(|x1| x1.sensors.get_numeric_in(location))
+    }
+
+    pub fn text_sensors_in(&self, location: LocationId) -> Vec<TextSensor> {
+        self.run(|x1| x1.sensors.get_text_in(location))
+    }
+}
+
+/// The locations of the X1, see `locations::Locations`.
+pub struct Locations<'a> {
+    x1: &'a X1,
+}
+
+impl Locations<'_> {
+    pub fn get(&self, id: LocationId) -> Option<Location> {
+        self.x1.run(|x1| x1.locations.get(id))
+    }
+
+    pub fn root(&self) -> Option<Location> {
+        self.x1.run(|x1| x1.locations.root())
+    }
+
+    pub fn children(&self, id: LocationId) -> Vec<Location> {
+        self.x1.run(|x1| x1.locations.children(id))
+    }
+
+    pub fn ancestors(&self, id: LocationId) -> Vec<Location> {
+        self.x1.run(|x1| x1.locations.ancestors(id))
+    }
+
+    pub fn path(&self, id: LocationId) -> Option<String> {
+        self.x1.run(|x1| x1.locations.path(id))
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Vec<Location> {
+        self.x1.run(|x1| x1.locations.find_by_name(name))
+    }
+
+    pub fn find_by_path(&self, path: &str) -> Option<Location> {
+        self.x1.run(|x1| x1.locations.find_by_path(path))
+    }
+
+    pub fn functions(&self, id: LocationId, recursive: bool) -> Vec<String> {
+        self.x1.run(|x1| x1.locations.functions(id, recursive))
+    }
+
+    pub fn tree(&self, id: LocationId) -> Option<LocationNode> {
+        self.x1.run(|x1| x1.locations.tree(id))
+    }
+}
+
+/// The trades of the X1, see `trades::Trades`.
+pub struct Trades<'a> {
+    x1: &'a X1,
+}
+
+impl Trades<'_> {
+    pub fn list(&self) -> Vec<String> {
+        self.x1.run(|x1| x1.trades.list())
+    }
+
+    pub fn get_all(&self) -> Vec<Trade> {
+        self.x1.run(|x1| x1.trades.get_all())
+    }
+
+    pub fn get(&self, trade: &str) -> Option<Trade> {
+        self.x1.run(|x1| x1.trades.get(trade))
+    }
+
+    pub fn functions(&self, trade: &str) -> Vec<String> {
+        self.x1.run(|x1| x1.trades.functions(trade))
+    }
+}
+
+/// A device together with the client it was read from. Its fields are reachable through
+/// `Deref`; the operations are those of the async device type.
+#[derive(Clone, Debug)]
+pub struct Device<T> {
+    x1: X1,
+    device: T,
+}
+
+impl<T> Deref for Device<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.device
+    }
+}
+
+impl<T> Device<T> {
+    pub fn into_inner(self) -> T {
+        self.device
+    }
+
+    fn run<'a, F, Fut>(&'a mut self, operation: F) -> Fut::Output
+    where
+        F: FnOnce(&'a mut T, &'a x1::X1) -> Fut,
+        Fut: Future,
+    {
+        let Device { x1, device } = self;
+        x1.runtime.block_on(operation(device, &x1.inner))
+    }
+}
+
+impl Device<Light> {
+    pub fn switch_on(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|light, x1| light.switch_on(x1))
+    }
+
+    pub fn switch_off(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|light, x1| light.switch_off(x1))
+    }
+
+    pub fn dimm(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|light, x1| light.dimm(x1, value))
+    }
+
+    pub fn tune(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|light, x1| light.tune(x1, value))
+    }
+
+    pub fn refresh(&mut self) -> Result<(), reqwest::Error> {
+        self.run(|light, x1| light.refresh(x1))
+    }
+}
+
+impl Device<Blind> {
+    pub fn up(&mut self) {
+        self.run(|blind, x1| blind.up(x1))
+    }
+
+    pub fn down(&mut self) {
+        self.run(|blind, x1| blind.down(x1))
+    }
+
+    pub fn step_up(&mut self) {
+        self.run(|blind, x1| blind.step_up(x1))
+    }
+
+    pub fn step_down(&mut self) {
+        self.run(|blind, x1| blind.step_down(x1))
+    }
+
+    pub fn set_position(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|blind, x1| blind.set_position(x1, value))
+    }
+
+    pub fn set_slat_position(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|blind, x1| blind.set_slat_position(x1, value))
+    }
+
+    pub fn refresh(&mut self) -> Result<(), reqwest::Error> {
+        self.run(|blind, x1| blind.refresh(x1))
+    }
+}
+
+impl Device<Thermostat> {
+    pub fn set_temperature(&mut self, value: f32) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|thermostat, x1| thermostat.set_temperature(x1, value))
+    }
+
+    pub fn set_mode(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|thermostat, x1| thermostat.set_mode(x1, value))
+    }
+
+    pub fn switch_on(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|thermostat, x1| thermostat.switch_on(x1))
+    }
+
+    pub fn switch_off(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|thermostat, x1| thermostat.switch_off(x1))
+    }
+}
+
+impl Device<FanCoil> {
+    pub fn set_temperature(&mut self, value: f32) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|fan_coil, x1| fan_coil.set_temperature(x1, value))
+    }
+
+    pub fn set_mode(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|fan_coil, x1| fan_coil.set_mode(x1, value))
+    }
+
+    pub fn set_fan_speed(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|fan_coil, x1| fan_coil.set_fan_speed(x1, value))
+    }
+
+    pub fn switch_on(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|fan_coil, x1| fan_coil.switch_on(x1))
+    }
+
+    pub fn switch_off(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|fan_coil, x1| fan_coil.switch_off(x1))
+    }
+}
+
+impl Device<Scene> {
+    pub fn execute(&mut self, scene: u16) {
+        self.run(|scene_set, x1| scene_set.execute(x1, scene))
+    }
+
+    pub fn teach(&mut self, scene: u16) {
+        self.run(|scene_set, x1| scene_set.teach(x1, scene))
+    }
+}
+
+impl Device<Trigger> {
+    pub fn press(&mut self) {
+        self.run(|trigger, x1| trigger.press(x1))
+    }
+}
+
+impl Device<PressAndHold> {
+    pub fn press(&mut self) {
+        self.run(|button, x1| button.press(x1))
+    }
+
+    pub fn release(&mut self) {
+        self.run(|button, x1| button.release(x1))
+    }
+
+    pub fn hold(&mut self, duration: Duration) {
+        self.run(|button, x1| button.hold(x1, duration))
+    }
+}
+
+impl Device<AudioPlayer> {
+    pub fn play(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.play(x1))
+    }
+
+    pub fn pause(&mut self) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.pause(x1))
+    }
+
+    pub fn next(&mut self) {
+        self.run(|player, x1| player.next(x1))
+    }
+
+    pub fn previous(&mut self) {
+        self.run(|player, x1| player.previous(x1))
+    }
+
+    pub fn set_volume(&mut self, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.set_volume(x1, value))
+    }
+
+    pub fn step_volume(&mut self, step: i16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.step_volume(x1, step))
+    }
+
+    pub fn set_mute(&mut self, mute: bool) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.set_mute(x1, mute))
+    }
+
+    pub fn set_shuffle(&mut self, shuffle: bool) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.set_shuffle(x1, shuffle))
+    }
+
+    pub fn set_repeat(&mut self, repeat: bool) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.set_repeat(x1, repeat))
+    }
+
+    pub fn set_playlist(&mut self, playlist: u16) -> Result<WriteOutcome, reqwest::Error> {
+        self.run(|player, x1| player.set_playlist(x1, playlist))
+    }
+}
+
+impl Device<Camera> {
+    pub fn snapshot(&mut self) -> Result<Vec<u8>, SnapshotError> {
+        self.run(|camera, x1| camera.snapshot(x1))
+    }
+}
+
+impl Device<GenericFunction> {
+    pub fn read(&mut self, name: &str) -> Result<DataValue, DataPointError> {
+        self.run(|function, x1| function.read(x1, name))
+    }
+
+    pub fn write(&mut self, name: &str, value: DataValue) -> Result<WriteOutcome, DataPointError> {
+        self.run(|function, x1| function.write(x1, name, value))
+    }
+}
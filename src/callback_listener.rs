@@ -57,8 +57,8 @@ struct ValueCallback {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Event {
-    uid: String,
-    value: String,
+    pub uid: String,
+    pub value: String,
 }
 
 #[derive(Clone, Debug)]
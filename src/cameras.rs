@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::x1::X1;
+
+#[derive(Clone, Debug)]
+pub struct Camera {
+    pub uid: String,
+    pub name: String,
+    pub url: Option<String>,
+    pub snapshot_url: Option<String>,
+    pub parameters: HashMap<String, String>,
+    pub location: Option<LocationId>,
+}
+
+impl Camera {
+    /// Fetches a single image from the camera's snapshot URL, falling back to its stream URL.
+    pub async fn snapshot(&self, x1: &X1) -> Result<Vec<u8>, SnapshotError> {
+        let url = self
+            .snapshot_url
+            .clone()
+            .or(self.url.clone())
+            .ok_or(SnapshotError::NoUrl)?;
+        let mut request = x1.client().get(url);
+        if let Some(user) = parameter(&self.parameters, "username") {
+            request = request.basic_auth(user, parameter(&self.parameters, "password"));
+        }
+        let resp = request.send().await?.error_for_status()?.bytes().await?;
+
+        Ok(resp.to_vec())
+    }
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The camera has neither a snapshot nor a stream URL configured.
+    NoUrl,
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::NoUrl => write!(f, "camera has no URL"),
+            SnapshotError::Request(e) => write!(f, "fetching snapshot failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<reqwest::Error> for SnapshotError {
+    fn from(e: reqwest::Error) -> Self {
+        SnapshotError::Request(e)
+    }
+}
+
+/// Picks a URL parameter of a camera or link. Snapshot URLs are keys that also mention
+/// "snapshot" or "image"; among several candidates the shortest key (e.g. `url` before
+/// `streamUrl`) wins, then the alphabetically first, so the choice never depends on the
+/// order of the map.
+pub(crate) fn url_parameter(
+    parameters: &HashMap<String, String>,
+    snapshot: bool,
+) -> Option<String> {
+    let mut keys: Vec<&String> = parameters
+        .keys()
+        .filter(|k| {
+            let k = k.to_lowercase();
+            k.contains("url") && (k.contains("snapshot") || k.contains("image")) == snapshot
+        })
+        .collect();
+    keys.sort_by_key(|k| (k.len(), k.to_lowercase()));
+    keys.first().map(|k| parameters[*k].clone())
+}
+
+/// Looks up a uiconfig parameter by key, ignoring case.
+fn parameter(parameters: &HashMap<String, String>, key: &str) -> Option<String> {
+    parameters
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.clone())
+}
+
+#[derive(Clone, Debug)]
+pub struct Cameras {
+    pub functions: X1Functions,
+}
+
+impl Cameras {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<Camera> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::CAMERA(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Camera> {
+        match self.functions.get(uid).await? {
+            X1Function::CAMERA(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn url_never_picks_the_snapshot_url() {
+        let parameters = parameters(&[
+            ("snapshotUrl", "http://cam/snapshot.jpg"),
+            ("streamUrl", "http://cam/stream"),
+            ("imageUrl", "http://cam/image.jpg"),
+        ]);
+        assert_eq!(
+            url_parameter(&parameters, false).as_deref(),
+            Some("http://cam/stream")
+        );
+        assert_eq!(
+            url_parameter(&parameters, true).as_deref(),
+            Some("http://cam/image.jpg")
+        );
+    }
+
+    #[test]
+    fn shortest_key_wins() {
+        let parameters = parameters(&[("streamUrl", "http://cam/stream"), ("url", "http://cam/")]);
+        assert_eq!(
+            url_parameter(&parameters, false).as_deref(),
+            Some("http://cam/")
+        );
+        assert_eq!(url_parameter(&parameters, true), None);
+    }
+}
@@ -0,0 +1,543 @@
+//! Capability traits implemented by the device types, so generic code can act on devices
+//! without knowing their concrete kind, e.g. switch off everything switchable in a room.
+
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+
+use crate::cameras::Camera;
+use crate::climate::{FanCoil, Thermostat};
+use crate::covers::Blind;
+use crate::function::X1Function;
+use crate::generic::GenericFunction;
+use crate::lights::Light;
+use crate::links::Link;
+use crate::locations::LocationId;
+use crate::media::AudioPlayer;
+use crate::queue::WriteOutcome;
+use crate::scenes::Scene;
+use crate::sensors::{BinarySensor, NumericSensor, TextSensor};
+use crate::triggers::{PressAndHold, Trigger};
+use crate::x1::X1;
+
+/// The result of a write through a capability trait.
+pub type WriteResult = Result<WriteOutcome, reqwest::Error>;
+
+/// Implemented by all device types.
+pub trait Readable {
+    fn uid(&self) -> &str;
+    fn name(&self) -> &str;
+    fn location(&self) -> Option<LocationId>;
+    /// The cached values of the device, keyed by data point name.
+    fn values(&self) -> HashMap<String, String>;
+}
+
+pub trait Switchable {
+    fn is_on(&self) -> Option<bool>;
+    fn switch_on<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult>;
+    fn switch_off<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult>;
+}
+
+pub trait Dimmable {
+    fn brightness(&self) -> Option<u16>;
+    fn set_brightness<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult>;
+}
+
+pub trait ColorTemperature {
+    fn color_temperature(&self) -> Option<u16>;
+    fn set_color_temperature<'a>(
+        &'a mut self,
+        x1: &'a X1,
+        value: u16,
+    ) -> BoxFuture<'a, WriteResult>;
+}
+
+pub trait Positionable {
+    fn position(&self) -> Option<u16>;
+    fn set_position<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult>;
+}
+
+pub trait Tiltable {
+    fn slat_position(&self) -> Option<u16>;
+    fn set_slat_position<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult>;
+}
+
+impl X1Function {
+    pub fn as_readable(&self) -> &dyn Readable {
+        match self {
+            X1Function::LIGHT(f) => f,
+            X1Function::BLIND(f) => f,
+            X1Function::THERMOSTAT(f) => f,
+            X1Function::FANCOIL(f) => f,
+            X1Function::SCENE(f) => f,
+            X1Function::TRIGGER(f) => f,
+            X1Function::PRESSANDHOLD(f) => f,
+            X1Function::BINARYSENSOR(f) => f,
+            X1Function::NUMERICSENSOR(f) => f,
+            X1Function::TEXTSENSOR(f) => f,
+            X1Function::AUDIOPLAYER(f) => f,
+            X1Function::CAMERA(f) => f,
+            X1Function::LINK(f) => f,
+            X1Function::GENERIC(f) => f,
+        }
+    }
+
+    pub fn as_switchable(&mut self) -> Option<&mut dyn Switchable> {
+        match self {
+            X1Function::LIGHT(f) if f.switch.is_some() => Some(f),
+            X1Function::THERMOSTAT(f) if f.on_off.is_some() => Some(f),
+            X1Function::FANCOIL(f) if f.on_off.is_some() => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_dimmable(&mut self) -> Option<&mut dyn Dimmable> {
+        match self {
+            X1Function::LIGHT(f) if f.dimmer.is_some() => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_color_temperature(&mut self) -> Option<&mut dyn ColorTemperature> {
+        match self {
+            X1Function::LIGHT(f) if f.tuner.is_some() => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_positionable(&mut self) -> Option<&mut dyn Positionable> {
+        match self {
+            X1Function::BLIND(f) if f.position.is_some() => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_tiltable(&mut self) -> Option<&mut dyn Tiltable> {
+        match self {
+            X1Function::BLIND(f) if f.slat_position.is_some() => Some(f),
+            _ => None,
+        }
+    }
+}
+
+fn insert<T: ToString>(values: &mut HashMap<String, String>, name: &str, value: Option<T>) {
+    if let Some(value) = value {
+        values.insert(name.to_string(), value.to_string());
+    }
+}
+
+impl Readable for Light {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        insert(&mut values, "OnOff", self.switch.as_ref().map(|dp| dp.val));
+        insert(
+            &mut values,
+            "Brightness",
+            self.dimmer.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Color-Temperature",
+            self.tuner.as_ref().map(|dp| dp.val),
+        );
+        insert(&mut values, "Color", self.color.as_ref().map(|dp| dp.val));
+        values
+    }
+}
+
+impl Switchable for Light {
+    fn is_on(&self) -> Option<bool> {
+        self.switch.as_ref().map(|dp| dp.val != 0)
+    }
+    fn switch_on<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Light::switch_on(self, x1))
+    }
+    fn switch_off<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Light::switch_off(self, x1))
+    }
+}
+
+impl Dimmable for Light {
+    fn brightness(&self) -> Option<u16> {
+        self.dimmer.as_ref().map(|dp| dp.val)
+    }
+    fn set_brightness<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult> {
+        Box::pin(self.dimm(x1, value))
+    }
+}
+
+impl ColorTemperature for Light {
+    fn color_temperature(&self) -> Option<u16> {
+        self.tuner.as_ref().map(|dp| dp.val)
+    }
+    fn set_color_temperature<'a>(
+        &'a mut self,
+        x1: &'a X1,
+        value: u16,
+    ) -> BoxFuture<'a, WriteResult> {
+        Box::pin(self.tune(x1, value))
+    }
+}
+
+impl Readable for Blind {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        insert(
+            &mut values,
+            "Step-Up-Down",
+            self.step_up_down.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Up-Down",
+            self.up_down.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Movement",
+            self.movement.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Position",
+            self.position.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Slat-Position",
+            self.slat_position.as_ref().map(|dp| dp.val),
+        );
+        values
+    }
+}
+
+impl Positionable for Blind {
+    fn position(&self) -> Option<u16> {
+        self.position.as_ref().map(|dp| dp.val)
+    }
+    fn set_position<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Blind::set_position(self, x1, value))
+    }
+}
+
+impl Tiltable for Blind {
+    fn slat_position(&self) -> Option<u16> {
+        self.slat_position.as_ref().map(|dp| dp.val)
+    }
+    fn set_slat_position<'a>(&'a mut self, x1: &'a X1, value: u16) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Blind::set_slat_position(self, x1, value))
+    }
+}
+
+impl Readable for Thermostat {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        insert(
+            &mut values,
+            "Current",
+            self.current.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Set-Point",
+            self.set_point.as_ref().map(|dp| dp.val),
+        );
+        insert(&mut values, "OnOff", self.on_off.as_ref().map(|dp| dp.val));
+        insert(&mut values, "Mode", self.mode.as_ref().map(|dp| dp.val));
+        insert(&mut values, "Status", self.status.as_ref().map(|dp| dp.val));
+        insert(
+            &mut values,
+            "Presence",
+            self.presence.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Heating",
+            self.heating.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Cooling",
+            self.cooling.as_ref().map(|dp| dp.val),
+        );
+        values
+    }
+}
+
+impl Switchable for Thermostat {
+    fn is_on(&self) -> Option<bool> {
+        self.on_off.as_ref().map(|dp| dp.val != 0)
+    }
+    fn switch_on<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Thermostat::switch_on(self, x1))
+    }
+    fn switch_off<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(Thermostat::switch_off(self, x1))
+    }
+}
+
+impl Readable for FanCoil {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        insert(
+            &mut values,
+            "Current",
+            self.current.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Set-Point",
+            self.set_point.as_ref().map(|dp| dp.val),
+        );
+        insert(&mut values, "OnOff", self.on_off.as_ref().map(|dp| dp.val));
+        insert(&mut values, "Mode", self.mode.as_ref().map(|dp| dp.val));
+        insert(
+            &mut values,
+            "Fan-Speed",
+            self.fan_speed.as_ref().map(|dp| dp.val),
+        );
+        values
+    }
+}
+
+impl Switchable for FanCoil {
+    fn is_on(&self) -> Option<bool> {
+        self.on_off.as_ref().map(|dp| dp.val != 0)
+    }
+    fn switch_on<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(FanCoil::switch_on(self, x1))
+    }
+    fn switch_off<'a>(&'a mut self, x1: &'a X1) -> BoxFuture<'a, WriteResult> {
+        Box::pin(FanCoil::switch_off(self, x1))
+    }
+}
+
+impl Readable for Scene {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl Readable for Trigger {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl Readable for PressAndHold {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl Readable for BinarySensor {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        if let Some(dp) = &self.status {
+            values.insert(dp.name.clone(), (dp.val as u16).to_string());
+        }
+        values
+    }
+}
+
+impl Readable for NumericSensor {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        if let Some(dp) = &self.status {
+            values.insert(dp.name.clone(), dp.val.to_string());
+        }
+        values
+    }
+}
+
+impl Readable for TextSensor {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        if let Some(dp) = &self.status {
+            values.insert(dp.name.clone(), dp.val.clone());
+        }
+        values
+    }
+}
+
+impl Readable for AudioPlayer {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        insert(&mut values, "Play", self.play.as_ref().map(|dp| dp.val));
+        insert(&mut values, "Volume", self.volume.as_ref().map(|dp| dp.val));
+        insert(&mut values, "Mute", self.mute.as_ref().map(|dp| dp.val));
+        insert(
+            &mut values,
+            "Shuffle",
+            self.shuffle.as_ref().map(|dp| dp.val),
+        );
+        insert(&mut values, "Repeat", self.repeat.as_ref().map(|dp| dp.val));
+        insert(
+            &mut values,
+            "Playlist",
+            self.playlist.as_ref().map(|dp| dp.val),
+        );
+        insert(
+            &mut values,
+            "Title",
+            self.title.as_ref().map(|dp| dp.val.clone()),
+        );
+        insert(
+            &mut values,
+            "Album",
+            self.album.as_ref().map(|dp| dp.val.clone()),
+        );
+        insert(
+            &mut values,
+            "Artist",
+            self.artist.as_ref().map(|dp| dp.val.clone()),
+        );
+        values
+    }
+}
+
+impl Readable for Camera {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl Readable for Link {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl Readable for GenericFunction {
+    fn uid(&self) -> &str {
+        &self.uid
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+    fn values(&self) -> HashMap<String, String> {
+        self.datapoints
+            .iter()
+            .map(|(name, dp)| (name.clone(), dp.val.to_string()))
+            .collect()
+    }
+}
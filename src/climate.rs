@@ -0,0 +1,325 @@
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::queue::WriteOutcome;
+use crate::x1::{X1, to_u16};
+
+#[derive(Clone, Debug)]
+pub enum ThermostatType {
+    ROOMTEMPERATURE,
+    HEATINGCOOLING,
+    UNKNOWN,
+}
+
+#[derive(Clone, Debug)]
+pub struct Thermostat {
+    pub uid: String,
+    pub name: String,
+    pub thermostattype: ThermostatType,
+    pub current: Option<Current>,
+    pub set_point: Option<SetPoint>,
+    pub on_off: Option<OnOff>,
+    pub mode: Option<Mode>,
+    pub status: Option<Status>,
+    pub presence: Option<Presence>,
+    pub heating: Option<Heating>,
+    pub cooling: Option<Cooling>,
+    pub location: Option<LocationId>,
+}
+
+impl Thermostat {
+    pub async fn set_temperature(
+        &mut self,
+        x1: &X1,
+        value: f32,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let set_point_uid = self.set_point.clone().expect("Error getting SetPoint").uid;
+        let outcome = x1
+            .set_raw_value(set_point_uid.clone(), value.to_string())
+            .await?;
+        if outcome.is_sent() {
+            self.set_point.as_mut().unwrap().val = value;
+            x1.functions.apply(&set_point_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_mode(&mut self, x1: &X1, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        let mode_uid = self.mode.clone().expect("Error getting Mode").uid;
+        let outcome = x1.set_value(mode_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.mode.as_mut().unwrap().val = value;
+            x1.functions.apply(&mode_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn switch_on(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let on_off_uid = self.on_off.clone().expect("Error getting OnOff").uid;
+        let outcome = x1.set_value(on_off_uid.clone(), 1).await?;
+        if outcome.is_sent() {
+            self.on_off.as_mut().unwrap().val = 1;
+            x1.functions.apply(&on_off_uid, "1").await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn switch_off(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let on_off_uid = self.on_off.clone().expect("Error getting OnOff").uid;
+        let outcome = x1.set_value(on_off_uid.clone(), 0).await?;
+        if outcome.is_sent() {
+            self.on_off.as_mut().unwrap().val = 0;
+            x1.functions.apply(&on_off_uid, "0").await;
+        }
+        Ok(outcome)
+    }
+
+    /// Applies a value reported by the X1 for one of this thermostat's data points.
+    /// Returns `false` if the uid does not belong to this thermostat.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        if let Some(current) = self.current.as_mut().filter(|dp| dp.uid == uid) {
+            current.val = value.parse().unwrap_or(current.val);
+        } else if let Some(set_point) = self.set_point.as_mut().filter(|dp| dp.uid == uid) {
+            set_point.val = value.parse().unwrap_or(set_point.val);
+        } else if let Some(on_off) = self.on_off.as_mut().filter(|dp| dp.uid == uid) {
+            on_off.val = to_u16(value);
+        } else if let Some(mode) = self.mode.as_mut().filter(|dp| dp.uid == uid) {
+            mode.val = to_u16(value);
+        } else if let Some(status) = self.status.as_mut().filter(|dp| dp.uid == uid) {
+            status.val = to_u16(value);
+        } else if let Some(presence) = self.presence.as_mut().filter(|dp| dp.uid == uid) {
+            presence.val = to_u16(value);
+        } else if let Some(heating) = self.heating.as_mut().filter(|dp| dp.uid == uid) {
+            heating.val = to_u16(value);
+        } else if let Some(cooling) = self.cooling.as_mut().filter(|dp| dp.uid == uid) {
+            cooling.val = to_u16(value);
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FanCoil {
+    pub uid: String,
+    pub name: String,
+    pub current: Option<Current>,
+    pub set_point: Option<SetPoint>,
+    pub on_off: Option<OnOff>,
+    pub mode: Option<Mode>,
+    pub fan_speed: Option<FanSpeed>,
+    pub location: Option<LocationId>,
+}
+
+impl FanCoil {
+    pub async fn set_temperature(
+        &mut self,
+        x1: &X1,
+        value: f32,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let set_point_uid = self.set_point.clone().expect("Error getting SetPoint").uid;
+        let outcome = x1
+            .set_raw_value(set_point_uid.clone(), value.to_string())
+            .await?;
+        if outcome.is_sent() {
+            self.set_point.as_mut().unwrap().val = value;
+            x1.functions.apply(&set_point_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_mode(&mut self, x1: &X1, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        let mode_uid = self.mode.clone().expect("Error getting Mode").uid;
+        let outcome = x1.set_value(mode_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.mode.as_mut().unwrap().val = value;
+            x1.functions.apply(&mode_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_fan_speed(
+        &mut self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let fan_speed_uid = self.fan_speed.clone().expect("Error getting FanSpeed").uid;
+        let outcome = x1.set_value(fan_speed_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.fan_speed.as_mut().unwrap().val = value;
+            x1.functions.apply(&fan_speed_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn switch_on(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let on_off_uid = self.on_off.clone().expect("Error getting OnOff").uid;
+        let outcome = x1.set_value(on_off_uid.clone(), 1).await?;
+        if outcome.is_sent() {
+            self.on_off.as_mut().unwrap().val = 1;
+            x1.functions.apply(&on_off_uid, "1").await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn switch_off(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let on_off_uid = self.on_off.clone().expect("Error getting OnOff").uid;
+        let outcome = x1.set_value(on_off_uid.clone(), 0).await?;
+        if outcome.is_sent() {
+            self.on_off.as_mut().unwrap().val = 0;
+            x1.functions.apply(&on_off_uid, "0").await;
+        }
+        Ok(outcome)
+    }
+
+    /// Applies a value reported by the X1 for one of this fan coil's data points.
+    /// Returns `false` if the uid does not belong to this fan coil.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        if let Some(current) = self.current.as_mut().filter(|dp| dp.uid == uid) {
+            current.val = value.parse().unwrap_or(current.val);
+        } else if let Some(set_point) = self.set_point.as_mut().filter(|dp| dp.uid == uid) {
+            set_point.val = value.parse().unwrap_or(set_point.val);
+        } else if let Some(on_off) = self.on_off.as_mut().filter(|dp| dp.uid == uid) {
+            on_off.val = to_u16(value);
+        } else if let Some(mode) = self.mode.as_mut().filter(|dp| dp.uid == uid) {
+            mode.val = to_u16(value);
+        } else if let Some(fan_speed) = self.fan_speed.as_mut().filter(|dp| dp.uid == uid) {
+            fan_speed.val = to_u16(value);
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Current {
+    pub uid: String,
+    pub val: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SetPoint {
+    pub uid: String,
+    pub val: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct OnOff {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Mode {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Status {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Presence {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Heating {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Cooling {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct FanSpeed {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Thermostats {
+    pub functions: X1Functions,
+}
+
+impl Thermostats {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<Thermostat> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::THERMOSTAT(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Thermostat> {
+        match self.functions.get(uid).await? {
+            X1Function::THERMOSTAT(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FanCoils {
+    pub functions: X1Functions,
+}
+
+impl FanCoils {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<FanCoil> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::FANCOIL(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<FanCoil> {
+        match self.functions.get(uid).await? {
+            X1Function::FANCOIL(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::queue::WriteOutcome;
+use crate::x1::{X1, same_value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Confirmation {
+    Confirmed,
+    /// The actuator reported a different value than the one written.
+    Mismatch(String),
+    TimedOut,
+    /// A newer write to the data point replaced this one before it was sent.
+    Coalesced,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConfirmOptions {
+    pub timeout: Duration,
+    /// Also reads the value back from the X1 while waiting, for setups without callbacks.
+    pub readback: bool,
+    /// How often the value is read back.
+    pub readback_interval: Duration,
+}
+
+impl Default for ConfirmOptions {
+    fn default() -> Self {
+        ConfirmOptions {
+            timeout: Duration::from_secs(3),
+            readback: true,
+            readback_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl X1 {
+    /// Like `set_value`, but waits until the X1 reports the written value.
+    pub async fn set_value_confirmed(
+        &self,
+        uid: String,
+        value: u16,
+        options: &ConfirmOptions,
+    ) -> Result<Confirmation, reqwest::Error> {
+        self.set_raw_value_confirmed(uid, value.to_string(), options)
+            .await
+    }
+
+    /// Like `set_raw_value`, but waits until the X1 reports the written value, either
+    /// through a callback or, if enabled, by reading it back. Everything happens within
+    /// the timeout.
+    pub async fn set_raw_value_confirmed(
+        &self,
+        uid: String,
+        value: String,
+        options: &ConfirmOptions,
+    ) -> Result<Confirmation, reqwest::Error> {
+        let mut reports = self.subscribe_reports();
+        match self.set_raw_value(uid.clone(), value.clone()).await? {
+            WriteOutcome::Sent(_) => (),
+            // Idempotent mode only skips values the data point already holds.
+            WriteOutcome::Skipped => return Ok(Confirmation::Confirmed),
+            WriteOutcome::Coalesced => return Ok(Confirmation::Coalesced),
+        }
+
+        let deadline = Instant::now() + options.timeout;
+        let mut readback = tokio::time::interval_at(
+            Instant::now() + options.readback_interval,
+            options.readback_interval.max(Duration::from_millis(1)),
+        );
+        let mut last_reported: Option<String> = None;
+        let mut readback_error = None;
+        loop {
+            tokio::select! {
+                report = tokio::time::timeout_at(deadline, reports.recv()) => match report {
+                    Ok(Ok((reported_uid, reported))) if reported_uid == uid => {
+                        if same_value(&reported, &value) {
+                            return Ok(Confirmation::Confirmed);
+                        }
+                        last_reported = Some(reported);
+                    }
+                    Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => (),
+                    Ok(Err(RecvError::Closed)) | Err(_) => break,
+                },
+                _ = readback.tick(), if options.readback => {
+                    let read = tokio::time::timeout_at(deadline, self.get_fn_raw_values(uid.clone()));
+                    match read.await {
+                        Ok(Ok(values)) => {
+                            if let Some(actual) = values.get(&uid) {
+                                if same_value(actual, &value) {
+                                    return Ok(Confirmation::Confirmed);
+                                }
+                                last_reported = Some(actual.clone());
+                            }
+                        }
+                        Ok(Err(e)) => readback_error = Some(e),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        match (last_reported, readback_error) {
+            (Some(actual), _) => Ok(Confirmation::Mismatch(actual)),
+            (None, Some(e)) => Err(e),
+            (None, None) => Ok(Confirmation::TimedOut),
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
-use crate::x1::X1;
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::queue::WriteOutcome;
+use crate::x1::{X1, to_u16};
 #[derive(Clone, Debug)]
 pub struct Blind {
     pub uid: String,
@@ -11,7 +11,7 @@ pub struct Blind {
     pub movement: Option<Movement>,
     pub position: Option<Position>,
     pub slat_position: Option<SlatPosition>,
-    pub location: Option<u16>,
+    pub location: Option<LocationId>,
 }
 
 impl Blind {
@@ -43,25 +43,107 @@ impl Blind {
 
         let _res = x1.set_value(movement_uid, 1).await;
     }
+
+    pub async fn set_position(
+        &mut self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let position_uid = self.position.clone().expect("Error getting Position").uid;
+        let outcome = x1.set_value(position_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.position.as_mut().unwrap().val = value;
+            x1.functions.apply(&position_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_slat_position(
+        &mut self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let slat_position_uid = self
+            .slat_position
+            .clone()
+            .expect("Error getting SlatPosition")
+            .uid;
+        let outcome = x1.set_value(slat_position_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.slat_position.as_mut().unwrap().val = value;
+            x1.functions
+                .apply(&slat_position_uid, &value.to_string())
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    /// Re-reads the values of this blind from the X1.
+    pub async fn refresh(&mut self, x1: &X1) -> Result<(), reqwest::Error> {
+        let values = x1.get_fn_raw_values(self.uid.clone()).await?;
+        for (uid, value) in values.iter() {
+            self.update(uid, value);
+        }
+        x1.apply_values(&values).await;
+        Ok(())
+    }
+
+    /// Applies a value reported by the X1 for one of this blind's data points.
+    /// Returns `false` if the uid does not belong to this blind.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        if let Some(step_up_down) = self.step_up_down.as_mut().filter(|dp| dp.uid == uid) {
+            step_up_down.val = to_u16(value);
+        } else if let Some(up_down) = self.up_down.as_mut().filter(|dp| dp.uid == uid) {
+            up_down.val = to_u16(value);
+        } else if let Some(movement) = self.movement.as_mut().filter(|dp| dp.uid == uid) {
+            movement.val = to_u16(value);
+        } else if let Some(position) = self.position.as_mut().filter(|dp| dp.uid == uid) {
+            position.val = to_u16(value);
+        } else if let Some(slat_position) = self.slat_position.as_mut().filter(|dp| dp.uid == uid) {
+            slat_position.val = to_u16(value);
+        } else {
+            return false;
+        }
+        true
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Blinds {
-    pub blinds: Arc<Mutex<Vec<Blind>>>,
+    pub functions: X1Functions,
 }
+
 impl Blinds {
     pub async fn list(&self) -> Vec<String> {
-        //println!("Lights:");
-        let mut list: Vec<String> = vec![];
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
 
-        for (_index, blind) in self.blinds.lock().await.iter().enumerate() {
-            list.push(blind.name.clone());
+    pub async fn get_all(&self) -> Vec<Blind> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::BLIND(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Blind> {
+        match self.functions.get(uid).await? {
+            X1Function::BLIND(function) => Some(function),
+            _ => None,
         }
-        list
     }
 
-    pub async fn get_all(&self) -> Vec<Blind> {
-        self.blinds.lock().await.clone()
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
     }
 }
 
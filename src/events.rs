@@ -0,0 +1,35 @@
+use crate::watchdog::ConnectionState;
+
+#[derive(Clone, Debug)]
+pub enum X1Event {
+    /// A cached data point value changed, e.g. through a callback or a refresh.
+    ValueChanged {
+        uid: String,
+        name: String,
+        datapoint: String,
+        value: String,
+    },
+    SceneInvoked {
+        uid: String,
+        name: String,
+        scene: u16,
+        teach: bool,
+    },
+    Triggered {
+        uid: String,
+        name: String,
+        value: u16,
+    },
+    Pressed {
+        uid: String,
+        name: String,
+    },
+    Released {
+        uid: String,
+        name: String,
+    },
+    /// The watchdog saw the connection to the X1 change.
+    ConnectionChanged {
+        state: ConnectionState,
+    },
+}
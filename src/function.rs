@@ -1,6 +1,24 @@
+use crate::cameras::Camera;
+use crate::climate::FanCoil;
+use crate::climate::Thermostat;
 use crate::covers::Blind;
+use crate::events::X1Event;
+use crate::generic::GenericFunction;
 use crate::lights::Light;
+use crate::links::Link;
+use crate::locations::LocationId;
+use crate::media::AudioPlayer;
+use crate::queue::WriteOutcome;
+use crate::scenes::Scene;
+use crate::sensors::BinarySensor;
+use crate::sensors::NumericSensor;
+use crate::sensors::TextSensor;
+use crate::snapshot::Snapshot;
+use crate::triggers::PressAndHold;
+use crate::triggers::Trigger;
+use crate::x1::X1;
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -8,9 +26,643 @@ use tokio::sync::Mutex;
 pub enum X1Function {
     LIGHT(Light),
     BLIND(Blind),
+    THERMOSTAT(Thermostat),
+    FANCOIL(FanCoil),
+    SCENE(Scene),
+    TRIGGER(Trigger),
+    PRESSANDHOLD(PressAndHold),
+    BINARYSENSOR(BinarySensor),
+    NUMERICSENSOR(NumericSensor),
+    TEXTSENSOR(TextSensor),
+    AUDIOPLAYER(AudioPlayer),
+    CAMERA(Camera),
+    LINK(Link),
+    GENERIC(GenericFunction),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FunctionKind {
+    LIGHT,
+    BLIND,
+    THERMOSTAT,
+    FANCOIL,
+    SCENE,
+    TRIGGER,
+    PRESSANDHOLD,
+    BINARYSENSOR,
+    NUMERICSENSOR,
+    TEXTSENSOR,
+    AUDIOPLAYER,
+    CAMERA,
+    LINK,
+    GENERIC,
+}
+
+impl X1Function {
+    pub fn kind(&self) -> FunctionKind {
+        match self {
+            X1Function::LIGHT(_) => FunctionKind::LIGHT,
+            X1Function::BLIND(_) => FunctionKind::BLIND,
+            X1Function::THERMOSTAT(_) => FunctionKind::THERMOSTAT,
+            X1Function::FANCOIL(_) => FunctionKind::FANCOIL,
+            X1Function::SCENE(_) => FunctionKind::SCENE,
+            X1Function::TRIGGER(_) => FunctionKind::TRIGGER,
+            X1Function::PRESSANDHOLD(_) => FunctionKind::PRESSANDHOLD,
+            X1Function::BINARYSENSOR(_) => FunctionKind::BINARYSENSOR,
+            X1Function::NUMERICSENSOR(_) => FunctionKind::NUMERICSENSOR,
+            X1Function::TEXTSENSOR(_) => FunctionKind::TEXTSENSOR,
+            X1Function::AUDIOPLAYER(_) => FunctionKind::AUDIOPLAYER,
+            X1Function::CAMERA(_) => FunctionKind::CAMERA,
+            X1Function::LINK(_) => FunctionKind::LINK,
+            X1Function::GENERIC(_) => FunctionKind::GENERIC,
+        }
+    }
+
+    pub fn uid(&self) -> &str {
+        match self {
+            X1Function::LIGHT(f) => &f.uid,
+            X1Function::BLIND(f) => &f.uid,
+            X1Function::THERMOSTAT(f) => &f.uid,
+            X1Function::FANCOIL(f) => &f.uid,
+            X1Function::SCENE(f) => &f.uid,
+            X1Function::TRIGGER(f) => &f.uid,
+            X1Function::PRESSANDHOLD(f) => &f.uid,
+            X1Function::BINARYSENSOR(f) => &f.uid,
+            X1Function::NUMERICSENSOR(f) => &f.uid,
+            X1Function::TEXTSENSOR(f) => &f.uid,
+            X1Function::AUDIOPLAYER(f) => &f.uid,
+            X1Function::CAMERA(f) => &f.uid,
+            X1Function::LINK(f) => &f.uid,
+            X1Function::GENERIC(f) => &f.uid,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            X1Function::LIGHT(f) => &f.name,
+            X1Function::BLIND(f) => &f.name,
+            X1Function::THERMOSTAT(f) => &f.name,
+            X1Function::FANCOIL(f) => &f.name,
+            X1Function::SCENE(f) => &f.name,
+            X1Function::TRIGGER(f) => &f.name,
+            X1Function::PRESSANDHOLD(f) => &f.name,
+            X1Function::BINARYSENSOR(f) => &f.name,
+            X1Function::NUMERICSENSOR(f) => &f.name,
+            X1Function::TEXTSENSOR(f) => &f.name,
+            X1Function::AUDIOPLAYER(f) => &f.name,
+            X1Function::CAMERA(f) => &f.name,
+            X1Function::LINK(f) => &f.name,
+            X1Function::GENERIC(f) => &f.name,
+        }
+    }
+
+    pub fn location(&self) -> Option<LocationId> {
+        match self {
+            X1Function::LIGHT(f) => f.location,
+            X1Function::BLIND(f) => f.location,
+            X1Function::THERMOSTAT(f) => f.location,
+            X1Function::FANCOIL(f) => f.location,
+            X1Function::SCENE(f) => f.location,
+            X1Function::TRIGGER(f) => f.location,
+            X1Function::PRESSANDHOLD(f) => f.location,
+            X1Function::BINARYSENSOR(f) => f.location,
+            X1Function::NUMERICSENSOR(f) => f.location,
+            X1Function::TEXTSENSOR(f) => f.location,
+            X1Function::AUDIOPLAYER(f) => f.location,
+            X1Function::CAMERA(f) => f.location,
+            X1Function::LINK(f) => f.location,
+            X1Function::GENERIC(f) => f.location,
+        }
+    }
+
+    pub fn set_location(&mut self, location: Option<LocationId>) {
+        match self {
+            X1Function::LIGHT(f) => f.location = location,
+            X1Function::BLIND(f) => f.location = location,
+            X1Function::THERMOSTAT(f) => f.location = location,
+            X1Function::FANCOIL(f) => f.location = location,
+            X1Function::SCENE(f) => f.location = location,
+            X1Function::TRIGGER(f) => f.location = location,
+            X1Function::PRESSANDHOLD(f) => f.location = location,
+            X1Function::BINARYSENSOR(f) => f.location = location,
+            X1Function::NUMERICSENSOR(f) => f.location = location,
+            X1Function::TEXTSENSOR(f) => f.location = location,
+            X1Function::AUDIOPLAYER(f) => f.location = location,
+            X1Function::CAMERA(f) => f.location = location,
+            X1Function::LINK(f) => f.location = location,
+            X1Function::GENERIC(f) => f.location = location,
+        }
+    }
+
+    /// The uids of the data points that hold state, as opposed to momentary ones such as
+    /// triggers, scene calls, steps or `Next`. Only these are cached for idempotent writes.
+    pub fn stateful_datapoints(&self) -> Vec<&str> {
+        match self {
+            X1Function::LIGHT(f) => [
+                f.switch.as_ref().map(|dp| dp.uid.as_str()),
+                f.dimmer.as_ref().map(|dp| dp.uid.as_str()),
+                f.tuner.as_ref().map(|dp| dp.uid.as_str()),
+                f.color.as_ref().map(|dp| dp.uid.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            X1Function::BLIND(f) => [
+                f.movement.as_ref().map(|dp| dp.uid.as_str()),
+                f.position.as_ref().map(|dp| dp.uid.as_str()),
+                f.slat_position.as_ref().map(|dp| dp.uid.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            X1Function::THERMOSTAT(f) => [
+                f.current.as_ref().map(|dp| dp.uid.as_str()),
+                f.set_point.as_ref().map(|dp| dp.uid.as_str()),
+                f.on_off.as_ref().map(|dp| dp.uid.as_str()),
+                f.mode.as_ref().map(|dp| dp.uid.as_str()),
+                f.status.as_ref().map(|dp| dp.uid.as_str()),
+                f.presence.as_ref().map(|dp| dp.uid.as_str()),
+                f.heating.as_ref().map(|dp| dp.uid.as_str()),
+                f.cooling.as_ref().map(|dp| dp.uid.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            X1Function::FANCOIL(f) => [
+                f.current.as_ref().map(|dp| dp.uid.as_str()),
+                f.set_point.as_ref().map(|dp| dp.uid.as_str()),
+                f.on_off.as_ref().map(|dp| dp.uid.as_str()),
+                f.mode.as_ref().map(|dp| dp.uid.as_str()),
+                f.fan_speed.as_ref().map(|dp| dp.uid.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            X1Function::BINARYSENSOR(f) => f.status.iter().map(|dp| dp.uid.as_str()).collect(),
+            X1Function::NUMERICSENSOR(f) => f.status.iter().map(|dp| dp.uid.as_str()).collect(),
+            X1Function::TEXTSENSOR(f) => f.status.iter().map(|dp| dp.uid.as_str()).collect(),
+            X1Function::AUDIOPLAYER(f) => [
+                f.play.as_ref().map(|dp| dp.uid.as_str()),
+                f.volume.as_ref().map(|dp| dp.uid.as_str()),
+                f.mute.as_ref().map(|dp| dp.uid.as_str()),
+                f.shuffle.as_ref().map(|dp| dp.uid.as_str()),
+                f.repeat.as_ref().map(|dp| dp.uid.as_str()),
+                f.playlist.as_ref().map(|dp| dp.uid.as_str()),
+                f.title.as_ref().map(|dp| dp.uid.as_str()),
+                f.album.as_ref().map(|dp| dp.uid.as_str()),
+                f.artist.as_ref().map(|dp| dp.uid.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            X1Function::SCENE(_)
+            | X1Function::TRIGGER(_)
+            | X1Function::PRESSANDHOLD(_)
+            | X1Function::CAMERA(_)
+            | X1Function::LINK(_)
+            | X1Function::GENERIC(_) => vec![],
+        }
+    }
+
+    /// Applies a value reported by the X1 for one of this function's data points.
+    /// Returns `false` if the uid does not belong to this function or it keeps no state.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        match self {
+            X1Function::LIGHT(f) => f.update(uid, value),
+            X1Function::BLIND(f) => f.update(uid, value),
+            X1Function::THERMOSTAT(f) => f.update(uid, value),
+            X1Function::FANCOIL(f) => f.update(uid, value),
+            X1Function::BINARYSENSOR(f) => f.update(uid, value),
+            X1Function::NUMERICSENSOR(f) => f.update(uid, value),
+            X1Function::TEXTSENSOR(f) => f.update(uid, value),
+            X1Function::AUDIOPLAYER(f) => f.update(uid, value),
+            X1Function::GENERIC(f) => f.update(uid, value),
+            X1Function::SCENE(_)
+            | X1Function::TRIGGER(_)
+            | X1Function::PRESSANDHOLD(_)
+            | X1Function::CAMERA(_)
+            | X1Function::LINK(_) => false,
+        }
+    }
+}
+
+/// The registry of all functions of the X1, keyed by function uid. Every device exists
+/// exactly once in here; `Lights`, `Blinds` and the other collections are views on it.
+/// Readers get a snapshot without waiting; writers take turns, each publishing an
+/// updated copy, so no change gets lost between two concurrent writers.
+#[derive(Clone, Debug, Default)]
 pub struct X1Functions {
-    pub functions: Arc<Mutex<HashMap<String, X1Function>>>,
+    pub functions: Arc<Snapshot<HashMap<String, X1Function>>>,
+    /// The last known raw value of every data point, keyed by data point uid.
+    pub(crate) values: Arc<Mutex<HashMap<String, String>>>,
+    writer: Arc<Mutex<()>>,
+}
+
+impl X1Functions {
+    pub async fn get(&self, uid: &str) -> Option<X1Function> {
+        self.functions.load().get(uid).cloned()
+    }
+
+    /// Returns all functions, sorted by name.
+    pub async fn get_all(&self) -> Vec<X1Function> {
+        let mut functions: Vec<X1Function> = self.functions.load().values().cloned().collect();
+        functions.sort_by(|a, b| a.name().cmp(b.name()));
+        functions
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.functions.load().is_empty()
+    }
+
+    /// Returns the last known raw value of a data point.
+    pub async fn cached_value(&self, uid: &str) -> Option<String> {
+        self.values.lock().await.get(uid).cloned()
+    }
+
+    pub async fn insert(&self, function: X1Function) {
+        self.update(|functions| {
+            functions.insert(function.uid().to_string(), function);
+        })
+        .await;
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.functions
+            .load()
+            .contains_key(uid)
+            .then(|| FunctionHandle {
+                uid: uid.to_string(),
+                functions: self.clone(),
+            })
+    }
+
+    /// Changes a copy of the registry and publishes it.
+    pub(crate) async fn update<R>(
+        &self,
+        change: impl FnOnce(&mut HashMap<String, X1Function>) -> R,
+    ) -> R {
+        let _writer = self.writer.lock().await;
+        let mut functions = self.functions.load().as_ref().clone();
+        let result = change(&mut functions);
+        self.functions.store(functions);
+        result
+    }
+
+    /// Applies a data point value to the function owning the data point.
+    /// Returns `false` if no function keeps state for the uid.
+    pub async fn apply(&self, uid: &str, value: &str) -> bool {
+        let stateful = self
+            .update(|functions| {
+                functions.values_mut().find_map(|function| {
+                    function
+                        .update(uid, value)
+                        .then(|| function.stateful_datapoints().contains(&uid))
+                })
+            })
+            .await;
+        let Some(stateful) = stateful else {
+            return false;
+        };
+        if stateful {
+            self.values
+                .lock()
+                .await
+                .insert(uid.to_string(), value.to_string());
+        }
+        true
+    }
+
+    /// Whether the data point holds state, see `X1Function::stateful_datapoints`.
+    pub async fn is_stateful(&self, uid: &str) -> bool {
+        self.functions
+            .load()
+            .values()
+            .any(|function| function.stateful_datapoints().contains(&uid))
+    }
+
+    /// Replaces all functions at once and caches the stateful ones of `values`, so
+    /// readers never see a partly built registry.
+    pub(crate) async fn replace(
+        &self,
+        functions: HashMap<String, X1Function>,
+        values: &HashMap<String, String>,
+    ) {
+        let _writer = self.writer.lock().await;
+        let mut cache = self.values.lock().await;
+        cache.clear();
+        for function in functions.values() {
+            for uid in function.stateful_datapoints() {
+                if let Some(value) = values.get(uid) {
+                    cache.insert(uid.to_string(), value.clone());
+                }
+            }
+        }
+        self.functions.store(functions);
+    }
+
+    /// Remembers the values of stateful data points, so idempotent writes can skip them.
+    pub(crate) async fn cache(&self, values: &HashMap<String, String>) {
+        let functions = self.functions.load();
+        let mut cache = self.values.lock().await;
+        for function in functions.values() {
+            for uid in function.stateful_datapoints() {
+                if let Some(value) = values.get(uid) {
+                    cache.insert(uid.to_string(), value.clone());
+                }
+            }
+        }
+    }
+
+    /// Applies data point values (keyed by data point uid) and returns a `ValueChanged`
+    /// event for every cached value that actually changed.
+    pub async fn apply_changes(&self, values: &HashMap<String, String>) -> Vec<X1Event> {
+        self.cache(values).await;
+        self.update(|functions| {
+            let mut events: Vec<X1Event> = vec![];
+            for function in functions.values_mut() {
+                let before = function.as_readable().values();
+                let mut updated = false;
+                for (uid, value) in values {
+                    updated |= function.update(uid, value);
+                }
+                if !updated {
+                    continue;
+                }
+                for (datapoint, value) in function.as_readable().values() {
+                    if before.get(&datapoint) != Some(&value) {
+                        events.push(X1Event::ValueChanged {
+                            uid: function.uid().to_string(),
+                            name: function.name().to_string(),
+                            datapoint,
+                            value,
+                        });
+                    }
+                }
+            }
+            events
+        })
+        .await
+    }
+}
+
+/// A cheap reference to a function in the registry. It always reads the current state,
+/// so it never goes stale the way a cloned `Light` or `Blind` does.
+#[derive(Clone, Debug)]
+pub struct FunctionHandle {
+    pub uid: String,
+    functions: X1Functions,
+}
+
+impl FunctionHandle {
+    pub async fn get(&self) -> Option<X1Function> {
+        self.functions.get(&self.uid).await
+    }
+
+    pub async fn light(&self) -> Option<Light> {
+        match self.get().await? {
+            X1Function::LIGHT(light) => Some(light),
+            _ => None,
+        }
+    }
+
+    pub async fn blind(&self) -> Option<Blind> {
+        match self.get().await? {
+            X1Function::BLIND(blind) => Some(blind),
+            _ => None,
+        }
+    }
+
+    pub async fn thermostat(&self) -> Option<Thermostat> {
+        match self.get().await? {
+            X1Function::THERMOSTAT(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn fan_coil(&self) -> Option<FanCoil> {
+        match self.get().await? {
+            X1Function::FANCOIL(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn scene(&self) -> Option<Scene> {
+        match self.get().await? {
+            X1Function::SCENE(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn trigger(&self) -> Option<Trigger> {
+        match self.get().await? {
+            X1Function::TRIGGER(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn press_and_hold(&self) -> Option<PressAndHold> {
+        match self.get().await? {
+            X1Function::PRESSANDHOLD(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn audio_player(&self) -> Option<AudioPlayer> {
+        match self.get().await? {
+            X1Function::AUDIOPLAYER(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn camera(&self) -> Option<Camera> {
+        match self.get().await? {
+            X1Function::CAMERA(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn link(&self) -> Option<Link> {
+        match self.get().await? {
+            X1Function::LINK(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn generic(&self) -> Option<GenericFunction> {
+        match self.get().await? {
+            X1Function::GENERIC(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    // The operations below act on the current state of the function and write their
+    // result through to the registry.
+
+    pub async fn switch_on(&self, x1: &X1) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function
+            .as_switchable()
+            .ok_or(OperationError::Unsupported)?;
+        Ok(device.switch_on(x1).await?)
+    }
+
+    pub async fn switch_off(&self, x1: &X1) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function
+            .as_switchable()
+            .ok_or(OperationError::Unsupported)?;
+        Ok(device.switch_off(x1).await?)
+    }
+
+    pub async fn set_brightness(
+        &self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function.as_dimmable().ok_or(OperationError::Unsupported)?;
+        Ok(device.set_brightness(x1, value).await?)
+    }
+
+    pub async fn set_color_temperature(
+        &self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function
+            .as_color_temperature()
+            .ok_or(OperationError::Unsupported)?;
+        Ok(device.set_color_temperature(x1, value).await?)
+    }
+
+    pub async fn set_position(&self, x1: &X1, value: u16) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function
+            .as_positionable()
+            .ok_or(OperationError::Unsupported)?;
+        Ok(device.set_position(x1, value).await?)
+    }
+
+    pub async fn set_slat_position(
+        &self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, OperationError> {
+        let mut function = self.get().await.ok_or(OperationError::Unsupported)?;
+        let device = function.as_tiltable().ok_or(OperationError::Unsupported)?;
+        Ok(device.set_slat_position(x1, value).await?)
+    }
+}
+
+#[derive(Debug)]
+pub enum OperationError {
+    /// The function is gone or does not support the operation.
+    Unsupported,
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationError::Unsupported => write!(f, "operation not supported"),
+            OperationError::Request(e) => write!(f, "writing to the X1 failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for OperationError {}
+
+impl From<reqwest::Error> for OperationError {
+    fn from(e: reqwest::Error) -> Self {
+        OperationError::Request(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::covers::{Position, StepUpDown};
+
+    fn registry() -> X1Functions {
+        let functions = X1Functions::default();
+        let blind = Blind {
+            uid: "b1".to_string(),
+            name: "Blind".to_string(),
+            step_up_down: Some(StepUpDown {
+                uid: "b1-step".to_string(),
+                val: 0,
+            }),
+            up_down: None,
+            movement: None,
+            position: Some(Position {
+                uid: "b1-position".to_string(),
+                val: 0,
+            }),
+            slat_position: None,
+            location: None,
+        };
+        functions.functions.store(HashMap::from([(
+            "b1".to_string(),
+            X1Function::BLIND(blind),
+        )]));
+        functions
+    }
+
+    #[tokio::test]
+    async fn only_stateful_datapoints_are_cached() {
+        let functions = registry();
+        assert!(functions.apply("b1-position", "40").await);
+        assert!(functions.apply("b1-step", "1").await);
+
+        assert_eq!(
+            functions.cached_value("b1-position").await.as_deref(),
+            Some("40")
+        );
+        assert_eq!(functions.cached_value("b1-step").await, None);
+    }
+
+    #[tokio::test]
+    async fn reported_values_fill_the_cache() {
+        let functions = registry();
+        let values = HashMap::from([
+            ("b1-position".to_string(), "70".to_string()),
+            ("b1-step".to_string(), "0".to_string()),
+        ]);
+        let events = functions.apply_changes(&values).await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            functions.cached_value("b1-position").await.as_deref(),
+            Some("70")
+        );
+        assert_eq!(functions.cached_value("b1-step").await, None);
+    }
+
+    #[tokio::test]
+    async fn unsupported_operations_are_reported() {
+        let functions = registry();
+        let x1 = X1::new("127.0.0.1", "user", "password");
+        let blind = functions.handle("b1").await.unwrap();
+
+        assert!(matches!(
+            blind.switch_on(&x1).await,
+            Err(OperationError::Unsupported)
+        ));
+        assert!(matches!(
+            blind.set_slat_position(&x1, 10).await,
+            Err(OperationError::Unsupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn replacing_the_registry_drops_stale_state() {
+        let functions = registry();
+        functions.apply("b1-position", "40").await;
+
+        functions.replace(HashMap::new(), &HashMap::new()).await;
+
+        assert!(functions.get("b1").await.is_none());
+        assert_eq!(functions.cached_value("b1-position").await, None);
+    }
 }
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::locations::LocationId;
+use crate::queue::WriteOutcome;
+use crate::x1::X1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValue {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl DataValue {
+    /// Parses a value as sent by the X1 into the narrowest matching type.
+    pub fn parse(value: &str) -> Self {
+        if let Ok(integer) = value.parse() {
+            DataValue::Integer(integer)
+        } else if let Ok(float) = value.parse() {
+            DataValue::Float(float)
+        } else {
+            DataValue::Text(value.to_string())
+        }
+    }
+}
+
+impl fmt::Display for DataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataValue::Integer(integer) => write!(f, "{integer}"),
+            DataValue::Float(float) => write!(f, "{float}"),
+            DataValue::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DataPointError {
+    /// The function has no data point with this name.
+    Unknown(String),
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for DataPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataPointError::Unknown(name) => write!(f, "no data point named {name}"),
+            DataPointError::Request(e) => write!(f, "accessing data point failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for DataPointError {}
+
+impl From<reqwest::Error> for DataPointError {
+    fn from(e: reqwest::Error) -> Self {
+        DataPointError::Request(e)
+    }
+}
+
+/// A function whose channel type is not modelled by a dedicated device type.
+#[derive(Clone, Debug)]
+pub struct GenericFunction {
+    pub uid: String,
+    pub name: String,
+    pub channel_type: String,
+    pub function_type: String,
+    pub datapoints: HashMap<String, GenericDataPoint>,
+    pub location: Option<LocationId>,
+}
+
+impl GenericFunction {
+    pub fn get(&self, name: &str) -> Option<&DataValue> {
+        self.datapoints.get(name).map(|dp| &dp.val)
+    }
+
+    /// Reads the current value of the data point `name` from the X1.
+    pub async fn read(&mut self, x1: &X1, name: &str) -> Result<DataValue, DataPointError> {
+        let datapoint = self
+            .datapoints
+            .get_mut(name)
+            .ok_or_else(|| DataPointError::Unknown(name.to_string()))?;
+        let values = x1.get_fn_raw_values(datapoint.uid.clone()).await?;
+        if let Some(value) = values.get(&datapoint.uid) {
+            datapoint.val = DataValue::parse(value);
+            x1.functions.apply(&datapoint.uid, value).await;
+        }
+        Ok(datapoint.val.clone())
+    }
+
+    pub async fn write(
+        &mut self,
+        x1: &X1,
+        name: &str,
+        value: DataValue,
+    ) -> Result<WriteOutcome, DataPointError> {
+        let datapoint = self
+            .datapoints
+            .get_mut(name)
+            .ok_or_else(|| DataPointError::Unknown(name.to_string()))?;
+        let outcome = x1
+            .set_raw_value(datapoint.uid.clone(), value.to_string())
+            .await?;
+        if outcome.is_sent() {
+            x1.functions.apply(&datapoint.uid, &value.to_string()).await;
+            datapoint.val = value;
+        }
+        Ok(outcome)
+    }
+
+    /// Applies a value reported by the X1 for one of this function's data points.
+    /// Returns `false` if the uid does not belong to this function.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        match self.datapoints.values_mut().find(|dp| dp.uid == uid) {
+            Some(datapoint) => {
+                datapoint.val = DataValue::parse(value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GenericDataPoint {
+    pub uid: String,
+    pub val: DataValue,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unknown_data_point_is_an_error() {
+        let x1 = X1::new("127.0.0.1", "user", "password");
+        let mut function = GenericFunction {
+            uid: "g1".to_string(),
+            name: "Generic".to_string(),
+            channel_type: "de.gira.schema.channels.Custom".to_string(),
+            function_type: "de.gira.schema.functions.Custom".to_string(),
+            datapoints: HashMap::new(),
+            location: None,
+        };
+
+        assert!(matches!(
+            function.read(&x1, "Level").await,
+            Err(DataPointError::Unknown(name)) if name == "Level"
+        ));
+        assert!(matches!(
+            function.write(&x1, "Level", DataValue::Integer(1)).await,
+            Err(DataPointError::Unknown(_))
+        ));
+    }
+}
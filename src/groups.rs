@@ -0,0 +1,177 @@
+use crate::function::X1Function;
+use crate::lights::Light;
+use crate::locations::LocationId;
+use crate::x1::X1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroupOutcome {
+    Sent,
+    /// Idempotent mode skipped the write, as the device already has the value.
+    Skipped,
+    Unsupported,
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct DeviceResult {
+    pub uid: String,
+    pub name: String,
+    pub outcome: GroupOutcome,
+}
+
+/// Per-device report of a group command.
+#[derive(Clone, Debug, Default)]
+pub struct GroupResult {
+    pub results: Vec<DeviceResult>,
+}
+
+impl GroupResult {
+    /// Whether every device has the new value, either written now or already before.
+    pub fn all_sent(&self) -> bool {
+        self.failed().is_empty()
+    }
+
+    /// The devices that did not get the new value.
+    pub fn failed(&self) -> Vec<DeviceResult> {
+        self.results
+            .iter()
+            .filter(|result| !matches!(result.outcome, GroupOutcome::Sent | GroupOutcome::Skipped))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A device affected by a group command and the write it needs, if it supports the command.
+struct GroupTarget {
+    uid: String,
+    name: String,
+    write: Option<(String, u16)>,
+}
+
+impl X1 {
+    pub async fn switch_on_lights_in(&self, location: LocationId, recursive: bool) -> GroupResult {
+        self.light_group(location, recursive, |light| {
+            light.switch.as_ref().map(|switch| (switch.uid.clone(), 1))
+        })
+        .await
+    }
+
+    pub async fn switch_off_lights_in(&self, location: LocationId, recursive: bool) -> GroupResult {
+        self.light_group(location, recursive, |light| {
+            light.switch.as_ref().map(|switch| (switch.uid.clone(), 0))
+        })
+        .await
+    }
+
+    pub async fn dim_lights_in(
+        &self,
+        location: LocationId,
+        recursive: bool,
+        percent: u16,
+    ) -> GroupResult {
+        let percent = percent.min(100);
+        self.light_group(location, recursive, |light| {
+            light
+                .dimmer
+                .as_ref()
+                .map(|dimmer| (dimmer.uid.clone(), percent))
+        })
+        .await
+    }
+
+    pub async fn set_blinds_in(
+        &self,
+        location: LocationId,
+        recursive: bool,
+        position: u16,
+    ) -> GroupResult {
+        let position = position.min(100);
+        let uids = self.locations.functions(location, recursive).await;
+        let functions = self.functions.functions.load();
+        let mut targets = vec![];
+        for uid in uids.iter() {
+            if let Some(X1Function::BLIND(blind)) = functions.get(uid) {
+                let write = blind
+                    .position
+                    .as_ref()
+                    .map(|pos| (pos.uid.clone(), position));
+                targets.push(GroupTarget {
+                    uid: blind.uid.clone(),
+                    name: blind.name.clone(),
+                    write,
+                });
+            }
+        }
+        drop(functions);
+
+        self.send_group(targets).await
+    }
+
+    async fn light_group(
+        &self,
+        location: LocationId,
+        recursive: bool,
+        command: impl Fn(&Light) -> Option<(String, u16)>,
+    ) -> GroupResult {
+        let uids = self.locations.functions(location, recursive).await;
+        let functions = self.functions.functions.load();
+        let mut targets = vec![];
+        for uid in uids.iter() {
+            if let Some(X1Function::LIGHT(light)) = functions.get(uid) {
+                let write = command(light);
+                targets.push(GroupTarget {
+                    uid: light.uid.clone(),
+                    name: light.name.clone(),
+                    write,
+                });
+            }
+        }
+        drop(functions);
+
+        self.send_group(targets).await
+    }
+
+    /// Sends the writes of all devices in one request and reports the outcome per device.
+    /// The cached state only takes the new values once the X1 accepted them.
+    async fn send_group(&self, targets: Vec<GroupTarget>) -> GroupResult {
+        let mut skipped = vec![];
+        let mut values: Vec<(String, u16)> = vec![];
+        for (uid, value) in targets.iter().filter_map(|target| target.write.clone()) {
+            if self.is_redundant(&uid, &value.to_string()).await {
+                skipped.push(uid);
+            } else {
+                values.push((uid, value));
+            }
+        }
+        let error = if values.is_empty() {
+            None
+        } else {
+            match self.set_values(values.clone()).await {
+                Ok(outcome) => {
+                    if outcome.is_sent() {
+                        for (uid, value) in &values {
+                            self.functions.apply(uid, &value.to_string()).await;
+                        }
+                    }
+                    None
+                }
+                Err(e) => Some(e.to_string()),
+            }
+        };
+
+        let results = targets
+            .into_iter()
+            .map(|target| DeviceResult {
+                uid: target.uid,
+                name: target.name,
+                outcome: match (target.write, &error) {
+                    (None, _) => GroupOutcome::Unsupported,
+                    (Some((uid, _)), _) if skipped.contains(&uid) => GroupOutcome::Skipped,
+                    (Some(_), Some(error)) => GroupOutcome::Failed(error.clone()),
+                    (Some(_), None) => GroupOutcome::Sent,
+                },
+            })
+            .collect();
+        GroupResult { results }
+    }
+}
@@ -1,6 +1,28 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod callback_listener;
+pub mod cameras;
+pub mod capabilities;
+pub mod climate;
+pub mod confirm;
 pub mod covers;
+pub mod events;
 pub mod function;
+pub mod generic;
+pub mod groups;
 pub mod lights;
+pub mod links;
 pub mod locations;
+pub mod media;
+pub mod metrics;
+pub mod polling;
+pub mod queue;
+pub mod retry;
+pub mod scenes;
+pub mod search;
+pub mod sensors;
+pub mod snapshot;
+pub mod trades;
+pub mod triggers;
+pub mod watchdog;
 pub mod x1;
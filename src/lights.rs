@@ -1,7 +1,7 @@
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
-use crate::x1::X1;
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::queue::WriteOutcome;
+use crate::x1::{X1, to_u16};
 
 #[derive(Clone, Debug)]
 pub enum LightType {
@@ -20,31 +20,74 @@ pub struct Light {
     pub dimmer: Option<Dimmer>,
     pub tuner: Option<Tuner>,
     pub color: Option<Color>,
-    pub location: Option<u16>,
+    pub location: Option<LocationId>,
 }
 
 impl Light {
-    pub async fn switch_on(&mut self, x1: &X1) {
+    pub async fn switch_on(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
         let switch_uid = self.switch.clone().expect("Error getting Switch").uid;
-        let _res = x1.set_value(switch_uid, 1).await;
-        self.switch.as_mut().unwrap().val = 1;
+        let outcome = x1.set_value(switch_uid.clone(), 1).await?;
+        if outcome.is_sent() {
+            self.switch.as_mut().unwrap().val = 1;
+            x1.functions.apply(&switch_uid, "1").await;
+        }
+        Ok(outcome)
     }
-    pub async fn switch_off(&mut self, x1: &X1) {
+    pub async fn switch_off(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
         let switch_uid = self.switch.clone().expect("Error getting Switch").uid;
-        let _res = x1.set_value(switch_uid, 0).await;
-        self.switch.as_mut().unwrap().val = 0;
+        let outcome = x1.set_value(switch_uid.clone(), 0).await?;
+        if outcome.is_sent() {
+            self.switch.as_mut().unwrap().val = 0;
+            x1.functions.apply(&switch_uid, "0").await;
+        }
+        Ok(outcome)
     }
 
-    pub async fn dimm(&mut self, x1: &X1, value: u16) {
+    pub async fn dimm(&mut self, x1: &X1, value: u16) -> Result<WriteOutcome, reqwest::Error> {
         let dimm_uid = self.dimmer.clone().expect("Error getting Dimmer").uid;
-        let _res = x1.set_value(dimm_uid, value).await;
-        self.dimmer.as_mut().unwrap().val = value;
+        let outcome = x1.set_value(dimm_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.dimmer.as_mut().unwrap().val = value;
+            x1.functions.apply(&dimm_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
     }
 
-    pub async fn tune(&mut self, x1: &X1, value: u16) {
+    pub async fn tune(&mut self, x1: &X1, value: u16) -> Result<WriteOutcome, reqwest::Error> {
         let tune_uid = self.tuner.clone().expect("Error getting Tuner").uid;
-        let _res = x1.set_value(tune_uid, value).await;
-        self.tuner.as_mut().unwrap().val = value;
+        let outcome = x1.set_value(tune_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.tuner.as_mut().unwrap().val = value;
+            x1.functions.apply(&tune_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    /// Re-reads the values of this light from the X1.
+    pub async fn refresh(&mut self, x1: &X1) -> Result<(), reqwest::Error> {
+        let values = x1.get_fn_raw_values(self.uid.clone()).await?;
+        for (uid, value) in values.iter() {
+            self.update(uid, value);
+        }
+        x1.apply_values(&values).await;
+        Ok(())
+    }
+
+    /// Applies a value reported by the X1 for one of this light's data points.
+    /// Returns `false` if the uid does not belong to this light.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        if let Some(switch) = self.switch.as_mut().filter(|dp| dp.uid == uid) {
+            switch.val = to_u16(value);
+        } else if let Some(dimmer) = self.dimmer.as_mut().filter(|dp| dp.uid == uid) {
+            dimmer.val = to_u16(value);
+        } else if let Some(tuner) = self.tuner.as_mut().filter(|dp| dp.uid == uid) {
+            tuner.val = to_u16(value);
+        } else if let Some(color) = self.color.as_mut().filter(|dp| dp.uid == uid) {
+            color.val = to_u16(value);
+        } else {
+            return false;
+        }
+        true
     }
 }
 #[derive(Clone, Debug)]
@@ -69,30 +112,40 @@ pub struct Color {
 }
 #[derive(Clone, Debug)]
 pub struct Lights {
-    pub light: Arc<Mutex<Vec<Light>>>,
+    pub functions: X1Functions,
 }
 
 impl Lights {
     pub async fn list(&self) -> Vec<String> {
-        //println!("Lights:");
-        let mut list: Vec<String> = vec![];
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
 
-        for (_index, light) in self.light.lock().await.iter().enumerate() {
-            let _kind = match light.lighttype {
-                LightType::COLOR => ": (Colored light)",
-                LightType::DIMM => ": (Dimmable Light)",
-                LightType::SWITCH => ": (Switchable Light)",
-                LightType::TUNE => ": (Tuneable Light)",
-                LightType::UNKNOWN => ": (Unknown Light)",
-            };
-            //println!("{index}: {} {kind}", light.name);
-            list.push(light.name.clone());
+    pub async fn get_all(&self) -> Vec<Light> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::LIGHT(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Light> {
+        match self.functions.get(uid).await? {
+            X1Function::LIGHT(function) => Some(function),
+            _ => None,
         }
-        list
     }
 
-    pub async fn get_all(&self) -> Vec<Light> {
-        self.light.lock().await.clone()
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
     }
 }
 
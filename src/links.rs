@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+
+#[derive(Clone, Debug)]
+pub struct Link {
+    pub uid: String,
+    pub name: String,
+    pub url: Option<String>,
+    pub parameters: HashMap<String, String>,
+    pub location: Option<LocationId>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Links {
+    pub functions: X1Functions,
+}
+
+impl Links {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<Link> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::LINK(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Link> {
+        match self.functions.get(uid).await? {
+            X1Function::LINK(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
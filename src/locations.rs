@@ -2,32 +2,171 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+
+use crate::snapshot::Snapshot;
+
+/// Identifies a location. Ids are derived from the location's path of display names in
+/// the Gira project, so they stay the same across processes and refreshes as long as the
+/// location is neither renamed nor moved.
+pub type LocationId = u32;
+
+/// Derives the id of a location from its key, the `/`-joined display names from the root
+/// down to the location (FNV-1a).
+pub fn location_id(key: &str) -> LocationId {
+    key.bytes().fold(0x811c9dc5, |hash: u32, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
 
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Location {
-    pub id: Option<u16>,
-    pub parent_location: Option<u16>,
+    pub id: Option<LocationId>,
+    pub parent_location: Option<LocationId>,
     pub displayName: String,
     pub functions: Option<Vec<String>>,
     pub locationType: String,
-    pub locations: Option<Vec<u16>>,
+    pub locations: Option<Vec<LocationId>>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Locations {
-    pub locations: Arc<Mutex<HashMap<u16, Location>>>,
-    pub set: Arc<Mutex<bool>>,
+    pub locations: Arc<Snapshot<HashMap<LocationId, Location>>>,
+}
+
+/// A location together with its sub-locations, as returned by `Locations::tree`.
+#[derive(Clone, Debug)]
+pub struct LocationNode {
+    pub location: Location,
+    pub children: Vec<LocationNode>,
 }
 
 impl Locations {
-    pub async fn get(&self, id: u16) -> Location {
+    pub async fn get(&self, id: LocationId) -> Option<Location> {
+        self.locations.load().get(&id).cloned()
+    }
+
+    /// Returns the top-level location the Gira project hangs from.
+    pub async fn root(&self) -> Option<Location> {
         self.locations
-            .lock()
-            .await
+            .load()
+            .values()
+            .find(|location| location.parent_location.is_none())
+            .cloned()
+    }
+
+    pub async fn children(&self, id: LocationId) -> Vec<Location> {
+        let locations = self.locations.load();
+        locations
             .get(&id)
-            .expect("error getting location")
-            .clone()
+            .and_then(|location| location.locations.clone())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|child| locations.get(child).cloned())
+            .collect()
+    }
+
+    /// Returns the parents of a location, starting with its direct parent and ending with the root.
+    pub async fn ancestors(&self, id: LocationId) -> Vec<Location> {
+        let locations = self.locations.load();
+        let mut ancestors: Vec<Location> = vec![];
+        let mut parent = locations.get(&id).and_then(|l| l.parent_location);
+        while let Some(parent_id) = parent {
+            match locations.get(&parent_id) {
+                Some(location) => {
+                    parent = location.parent_location;
+                    ancestors.push(location.clone());
+                }
+                None => break,
+            }
+        }
+        ancestors
+    }
+
+    /// Returns the display names from below the root down to the location, e.g. `Ground floor/Kitchen`.
+    pub async fn path(&self, id: LocationId) -> Option<String> {
+        let location = self.get(id).await?;
+        let mut names: Vec<String> = self
+            .ancestors(id)
+            .await
+            .into_iter()
+            .filter(|ancestor| ancestor.parent_location.is_some())
+            .map(|ancestor| ancestor.displayName)
+            .collect();
+        names.reverse();
+        if location.parent_location.is_some() {
+            names.push(location.displayName);
+        }
+        Some(names.join("/"))
+    }
+
+    pub async fn find_by_name(&self, name: &str) -> Vec<Location> {
+        self.locations
+            .load()
+            .values()
+            .filter(|location| location.displayName == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Looks up a location by its path below the root, e.g. `Ground floor/Kitchen`.
+    pub async fn find_by_path(&self, path: &str) -> Option<Location> {
+        let mut location = self.root().await?;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            location = self
+                .children(location.id?)
+                .await
+                .into_iter()
+                .find(|child| child.displayName == name)?;
+        }
+        Some(location)
+    }
+
+    /// Returns the uids of the functions in a location and, if `recursive` is set,
+    /// in all of its sub-locations.
+    pub async fn functions(&self, id: LocationId, recursive: bool) -> Vec<String> {
+        let locations = self.locations.load();
+        let mut functions: Vec<String> = vec![];
+        let mut pending: Vec<LocationId> = vec![id];
+        while let Some(id) = pending.pop() {
+            if let Some(location) = locations.get(&id) {
+                functions.extend(location.functions.clone().unwrap_or_default());
+                if recursive {
+                    pending.extend(location.locations.clone().unwrap_or_default());
+                }
+            }
+        }
+        functions
+    }
+
+    pub async fn tree(&self, id: LocationId) -> Option<LocationNode> {
+        let locations = self.locations.load();
+        build_node(&locations, id)
+    }
+}
+
+fn build_node(locations: &HashMap<LocationId, Location>, id: LocationId) -> Option<LocationNode> {
+    let location = locations.get(&id)?.clone();
+    let children = location
+        .locations
+        .clone()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|child| build_node(locations, *child))
+        .collect();
+    Some(LocationNode { location, children })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_ids_are_pinned() {
+        // Changing these breaks every id persisted by users of the crate.
+        assert_eq!(location_id(""), 2166136261);
+        assert_eq!(location_id("a"), 3826002220);
+        assert_eq!(location_id("/Ground floor"), 4083733551);
+        assert_eq!(location_id("/Ground floor/Kitchen"), 4252758392);
     }
 }
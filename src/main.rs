@@ -7,7 +7,7 @@ use tokio::sync::mpsc::*;
 #[tokio::main]
 async fn main() {
     let myx1 = X1::new("10.10.1.12", "Username", "My$up3rs3cur3P4$$w0rd");
-    myx1.connect().await;
+    myx1.connect().await.expect("Error connecting to the X1");
 
     let (tx, mut rx): (Sender<Event>, Receiver<Event>) = mpsc::channel(32);
 
@@ -18,9 +18,8 @@ async fn main() {
     //let a = myx1.functions.functions.lock().await;
 
     tokio::spawn(async move { callback_listener::callback_listener(tx).await });
-    loop {
-        if let Ok(evt) = rx.try_recv() {
-            println!("New Event: {evt:?}!");
-        }
+    while let Some(evt) = rx.recv().await {
+        println!("New Event: {evt:?}!");
+        myx1.handle_event(&evt).await;
     }
 }
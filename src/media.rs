@@ -0,0 +1,293 @@
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::queue::WriteOutcome;
+use crate::x1::{X1, to_u16};
+
+#[derive(Clone, Debug)]
+pub enum AudioType {
+    PLAYLIST,
+    SONOS,
+    UNKNOWN,
+}
+
+#[derive(Clone, Debug)]
+pub struct AudioPlayer {
+    pub uid: String,
+    pub name: String,
+    pub audiotype: AudioType,
+    pub play: Option<Play>,
+    pub volume: Option<Volume>,
+    pub mute: Option<Mute>,
+    pub previous: Option<Previous>,
+    pub next: Option<Next>,
+    pub shuffle: Option<Shuffle>,
+    pub repeat: Option<Repeat>,
+    pub playlist: Option<Playlist>,
+    pub title: Option<Title>,
+    pub album: Option<Album>,
+    pub artist: Option<Artist>,
+    pub location: Option<LocationId>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NowPlaying {
+    pub title: Option<String>,
+    pub album: Option<String>,
+    pub artist: Option<String>,
+}
+
+impl AudioPlayer {
+    pub async fn play(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let play_uid = self.play.clone().expect("Error getting Play").uid;
+        let outcome = x1.set_value(play_uid.clone(), 1).await?;
+        if outcome.is_sent() {
+            self.play.as_mut().unwrap().val = 1;
+            x1.functions.apply(&play_uid, "1").await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn pause(&mut self, x1: &X1) -> Result<WriteOutcome, reqwest::Error> {
+        let play_uid = self.play.clone().expect("Error getting Play").uid;
+        let outcome = x1.set_value(play_uid.clone(), 0).await?;
+        if outcome.is_sent() {
+            self.play.as_mut().unwrap().val = 0;
+            x1.functions.apply(&play_uid, "0").await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn next(&self, x1: &X1) {
+        let next_uid = self.next.clone().expect("Error getting Next").uid;
+        let _res = x1.set_value(next_uid, 1).await;
+    }
+
+    pub async fn previous(&self, x1: &X1) {
+        let previous_uid = self.previous.clone().expect("Error getting Previous").uid;
+        let _res = x1.set_value(previous_uid, 1).await;
+    }
+
+    pub async fn set_volume(
+        &mut self,
+        x1: &X1,
+        value: u16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let value = value.min(100);
+        let volume_uid = self.volume.clone().expect("Error getting Volume").uid;
+        let outcome = x1.set_value(volume_uid.clone(), value).await?;
+        if outcome.is_sent() {
+            self.volume.as_mut().unwrap().val = value;
+            x1.functions.apply(&volume_uid, &value.to_string()).await;
+        }
+        Ok(outcome)
+    }
+
+    /// Changes the volume relative to the last known volume, clamped to 0..=100.
+    pub async fn step_volume(
+        &mut self,
+        x1: &X1,
+        step: i16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let current = self.volume.clone().expect("Error getting Volume").val;
+        let value = (current as i16).saturating_add(step).clamp(0, 100) as u16;
+        self.set_volume(x1, value).await
+    }
+
+    pub async fn set_mute(&mut self, x1: &X1, mute: bool) -> Result<WriteOutcome, reqwest::Error> {
+        let mute_uid = self.mute.clone().expect("Error getting Mute").uid;
+        let outcome = x1.set_value(mute_uid.clone(), mute as u16).await?;
+        if outcome.is_sent() {
+            self.mute.as_mut().unwrap().val = mute as u16;
+            x1.functions
+                .apply(&mute_uid, &(mute as u16).to_string())
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_shuffle(
+        &mut self,
+        x1: &X1,
+        shuffle: bool,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let shuffle_uid = self.shuffle.clone().expect("Error getting Shuffle").uid;
+        let outcome = x1.set_value(shuffle_uid.clone(), shuffle as u16).await?;
+        if outcome.is_sent() {
+            self.shuffle.as_mut().unwrap().val = shuffle as u16;
+            x1.functions
+                .apply(&shuffle_uid, &(shuffle as u16).to_string())
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_repeat(
+        &mut self,
+        x1: &X1,
+        repeat: bool,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let repeat_uid = self.repeat.clone().expect("Error getting Repeat").uid;
+        let outcome = x1.set_value(repeat_uid.clone(), repeat as u16).await?;
+        if outcome.is_sent() {
+            self.repeat.as_mut().unwrap().val = repeat as u16;
+            x1.functions
+                .apply(&repeat_uid, &(repeat as u16).to_string())
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    pub async fn set_playlist(
+        &mut self,
+        x1: &X1,
+        playlist: u16,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let playlist_uid = self.playlist.clone().expect("Error getting Playlist").uid;
+        let outcome = x1.set_value(playlist_uid.clone(), playlist).await?;
+        if outcome.is_sent() {
+            self.playlist.as_mut().unwrap().val = playlist;
+            x1.functions
+                .apply(&playlist_uid, &playlist.to_string())
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    pub fn now_playing(&self) -> NowPlaying {
+        NowPlaying {
+            title: self.title.as_ref().map(|dp| dp.val.clone()),
+            album: self.album.as_ref().map(|dp| dp.val.clone()),
+            artist: self.artist.as_ref().map(|dp| dp.val.clone()),
+        }
+    }
+
+    /// Applies a value reported by the X1 for one of this player's data points.
+    /// Returns `false` if the uid does not belong to this player.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        if let Some(play) = self.play.as_mut().filter(|dp| dp.uid == uid) {
+            play.val = to_u16(value);
+        } else if let Some(volume) = self.volume.as_mut().filter(|dp| dp.uid == uid) {
+            volume.val = to_u16(value);
+        } else if let Some(mute) = self.mute.as_mut().filter(|dp| dp.uid == uid) {
+            mute.val = to_u16(value);
+        } else if let Some(shuffle) = self.shuffle.as_mut().filter(|dp| dp.uid == uid) {
+            shuffle.val = to_u16(value);
+        } else if let Some(repeat) = self.repeat.as_mut().filter(|dp| dp.uid == uid) {
+            repeat.val = to_u16(value);
+        } else if let Some(playlist) = self.playlist.as_mut().filter(|dp| dp.uid == uid) {
+            playlist.val = to_u16(value);
+        } else if let Some(title) = self.title.as_mut().filter(|dp| dp.uid == uid) {
+            title.val = value.to_string();
+        } else if let Some(album) = self.album.as_mut().filter(|dp| dp.uid == uid) {
+            album.val = value.to_string();
+        } else if let Some(artist) = self.artist.as_mut().filter(|dp| dp.uid == uid) {
+            artist.val = value.to_string();
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Play {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Volume {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Mute {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Previous {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Next {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Shuffle {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Repeat {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Playlist {
+    pub uid: String,
+    pub val: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Title {
+    pub uid: String,
+    pub val: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Album {
+    pub uid: String,
+    pub val: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Artist {
+    pub uid: String,
+    pub val: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct AudioPlayers {
+    pub functions: X1Functions,
+}
+
+impl AudioPlayers {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<AudioPlayer> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::AUDIOPLAYER(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<AudioPlayer> {
+        match self.functions.get(uid).await? {
+            X1Function::AUDIOPLAYER(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// A snapshot of the counters kept by an `X1`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    /// Writes that were skipped because the data point already had the value.
+    pub skipped_writes: u64,
+    /// Writes waiting in the command queue right now.
+    pub queue_depth: u64,
+    /// Writes that were dropped in favour of a newer value for the same data point.
+    pub coalesced_writes: u64,
+    /// Time the most recent write spent waiting in the command queue.
+    pub last_queue_latency: Duration,
+    pub max_queue_latency: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MetricsRecorder {
+    pub(crate) skipped_writes: AtomicU64,
+    pub(crate) queue_depth: AtomicU64,
+    pub(crate) coalesced_writes: AtomicU64,
+    last_queue_latency_us: AtomicU64,
+    max_queue_latency_us: AtomicU64,
+}
+
+impl MetricsRecorder {
+    pub(crate) fn record_queue_latency(&self, latency: Duration) {
+        let micros = latency.as_micros() as u64;
+        self.last_queue_latency_us.store(micros, Ordering::Relaxed);
+        self.max_queue_latency_us
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> Metrics {
+        Metrics {
+            skipped_writes: self.skipped_writes.load(Ordering::Relaxed),
+            queue_depth: self.queue_depth.load(Ordering::Relaxed),
+            coalesced_writes: self.coalesced_writes.load(Ordering::Relaxed),
+            last_queue_latency: Duration::from_micros(
+                self.last_queue_latency_us.load(Ordering::Relaxed),
+            ),
+            max_queue_latency: Duration::from_micros(
+                self.max_queue_latency_us.load(Ordering::Relaxed),
+            ),
+        }
+    }
+}
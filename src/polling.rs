@@ -0,0 +1,141 @@
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::function::FunctionKind;
+use crate::x1::X1;
+
+#[derive(Clone, Debug)]
+pub enum PollMode {
+    /// Reads the eventable data points one by one, `size` requests at a time.
+    BATCHED { size: usize },
+    /// Reads all values of a function with one `get_fn_values` request per function.
+    PERFUNCTION,
+}
+
+/// Configures the polling fallback for setups where the X1 cannot reach a callback URL.
+#[derive(Clone, Debug)]
+pub struct PollingConfig {
+    pub mode: PollMode,
+    pub interval: Duration,
+    /// Overrides `interval` for single device kinds, e.g. to poll sensors less often.
+    pub intervals: HashMap<FunctionKind, Duration>,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        PollingConfig {
+            mode: PollMode::PERFUNCTION,
+            interval: Duration::from_secs(10),
+            intervals: HashMap::new(),
+        }
+    }
+}
+
+impl PollingConfig {
+    pub fn interval_for(&self, kind: FunctionKind) -> Duration {
+        self.intervals.get(&kind).copied().unwrap_or(self.interval)
+    }
+}
+
+impl X1 {
+    /// Starts polling the X1 in the background. Changed values are published as the same
+    /// `ValueChanged` events the callback path produces. Abort the handle to stop polling.
+    pub fn start_polling(&self, config: PollingConfig) -> JoinHandle<()> {
+        let x1 = self.clone();
+        tokio::spawn(async move {
+            let tick = config
+                .intervals
+                .values()
+                .copied()
+                .chain([config.interval])
+                .min()
+                .unwrap_or(config.interval);
+            let mut last_poll: HashMap<FunctionKind, Instant> = HashMap::new();
+            let mut ticker = tokio::time::interval(tick);
+            loop {
+                ticker.tick().await;
+                let now = Instant::now();
+                let due: Vec<FunctionKind> = x1
+                    .functions
+                    .get_all()
+                    .await
+                    .iter()
+                    .map(|function| function.kind())
+                    .filter(|kind| {
+                        last_poll
+                            .get(kind)
+                            .is_none_or(|last| now - *last >= config.interval_for(*kind))
+                    })
+                    .collect();
+                if due.is_empty() {
+                    continue;
+                }
+                for kind in due.iter() {
+                    last_poll.insert(*kind, now);
+                }
+                // Failed reads are logged by `poll` itself.
+                let _ = x1.poll(&config.mode, &due).await;
+            }
+        })
+    }
+
+    /// Reads the eventable data points of all functions of the given kinds once and
+    /// applies the values to the cached devices. A failed read does not stop the cycle;
+    /// the first error is returned once all other reads are done.
+    pub async fn poll(
+        &self,
+        mode: &PollMode,
+        kinds: &[FunctionKind],
+    ) -> Result<(), reqwest::Error> {
+        let eventable = self.eventable_datapoints().await;
+        let functions: Vec<String> = self
+            .functions
+            .get_all()
+            .await
+            .iter()
+            .filter(|function| kinds.contains(&function.kind()))
+            .filter(|function| eventable.contains_key(function.uid()))
+            .map(|function| function.uid().to_string())
+            .collect();
+
+        let mut failure: Option<reqwest::Error> = None;
+        let mut record = |uid: &str, e: reqwest::Error| {
+            tracing::warn!("Polling {uid} failed: {e}");
+            failure.get_or_insert(e);
+        };
+        match mode {
+            PollMode::PERFUNCTION => {
+                for uid in functions {
+                    match self.get_fn_raw_values(uid.clone()).await {
+                        Ok(values) => self.apply_values(&values).await,
+                        Err(e) => record(&uid, e),
+                    }
+                }
+            }
+            PollMode::BATCHED { size } => {
+                let datapoints: Vec<String> = functions
+                    .iter()
+                    .flat_map(|uid| eventable[uid].clone())
+                    .collect();
+                for batch in datapoints.chunks((*size).max(1)) {
+                    let reads = batch.iter().map(|uid| self.get_fn_raw_values(uid.clone()));
+                    let mut values: HashMap<String, String> = HashMap::new();
+                    for (uid, result) in batch.iter().zip(join_all(reads).await) {
+                        match result {
+                            Ok(read) => values.extend(read),
+                            Err(e) => record(uid, e),
+                        }
+                    }
+                    self.apply_values(&values).await;
+                }
+            }
+        }
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tokio::sync::{Mutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::metrics::MetricsRecorder;
+
+#[derive(Clone, Debug)]
+pub struct QueueConfig {
+    /// Maximum number of writes sent per second, `None` for no limit.
+    pub max_rate: Option<u32>,
+    /// Maximum number of writes in flight at the same time.
+    pub max_concurrency: usize,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        QueueConfig {
+            max_rate: Some(10),
+            max_concurrency: 2,
+        }
+    }
+}
+
+/// What became of a write.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WriteOutcome {
+    /// The X1 accepted the write; holds its response.
+    Sent(String),
+    /// Idempotent mode skipped the write, as the data point already holds the value.
+    Skipped,
+    /// A newer write to the same data point replaced this one before it was sent.
+    Coalesced,
+}
+
+impl WriteOutcome {
+    pub fn is_sent(&self) -> bool {
+        matches!(self, WriteOutcome::Sent(_))
+    }
+}
+
+/// Every write to the X1 waits here for its turn. Writes to the same data point are sent
+/// one after the other. A write to a stateful data point that was overtaken by a newer
+/// value while waiting is dropped, so only the latest slider value gets sent; momentary
+/// writes such as `Next` or a trigger are never dropped.
+#[derive(Debug)]
+pub(crate) struct CommandQueue {
+    config: QueueConfig,
+    concurrency: Arc<Semaphore>,
+    next_slot: Mutex<Instant>,
+    datapoints: Mutex<HashMap<String, DataPointSlot>>,
+    metrics: Arc<MetricsRecorder>,
+}
+
+#[derive(Debug, Default)]
+struct DataPointSlot {
+    latest: u64,
+    lock: Arc<Mutex<()>>,
+}
+
+/// Counts a write in the queue depth for as long as it waits, including when the waiting
+/// caller gives up (e.g. on a timeout) and drops its future.
+struct Waiting<'a> {
+    metrics: &'a MetricsRecorder,
+}
+
+impl<'a> Waiting<'a> {
+    fn new(metrics: &'a MetricsRecorder) -> Self {
+        metrics.queue_depth.fetch_add(1, Ordering::Relaxed);
+        Waiting { metrics }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.metrics.queue_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Permission to send a write. The write counts as in flight until this is dropped.
+pub(crate) struct QueuePermit {
+    _datapoint: Option<OwnedMutexGuard<()>>,
+    _concurrency: OwnedSemaphorePermit,
+}
+
+impl CommandQueue {
+    pub(crate) fn new(config: QueueConfig, metrics: Arc<MetricsRecorder>) -> Self {
+        CommandQueue {
+            concurrency: Arc::new(Semaphore::new(config.max_concurrency.max(1))),
+            config,
+            next_slot: Mutex::new(Instant::now()),
+            datapoints: Mutex::new(HashMap::new()),
+            metrics,
+        }
+    }
+
+    /// Waits until a write to the data point may be sent. With `coalesce` set, returns
+    /// `None` if a newer coalescing write to the same data point was queued in the meantime.
+    pub(crate) async fn acquire(&self, uid: &str, coalesce: bool) -> Option<QueuePermit> {
+        let enqueued = Instant::now();
+        let waiting = Waiting::new(&self.metrics);
+
+        let (ticket, lock) = {
+            let mut datapoints = self.datapoints.lock().await;
+            let slot = datapoints.entry(uid.to_string()).or_default();
+            if coalesce {
+                slot.latest += 1;
+            }
+            (slot.latest, slot.lock.clone())
+        };
+        let datapoint = lock.lock_owned().await;
+        let superseded = coalesce
+            && self
+                .datapoints
+                .lock()
+                .await
+                .get(uid)
+                .is_some_and(|slot| slot.latest != ticket);
+        if superseded {
+            self.metrics
+                .coalesced_writes
+                .fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let permit = self.wait_for_turn(enqueued, waiting).await;
+        Some(QueuePermit {
+            _datapoint: Some(datapoint),
+            ..permit
+        })
+    }
+
+    /// Waits until a write touching several data points may be sent. Such writes are
+    /// never coalesced.
+    pub(crate) async fn acquire_batch(&self) -> QueuePermit {
+        let enqueued = Instant::now();
+        let waiting = Waiting::new(&self.metrics);
+        self.wait_for_turn(enqueued, waiting).await
+    }
+
+    async fn wait_for_turn(&self, enqueued: Instant, waiting: Waiting<'_>) -> QueuePermit {
+        let concurrency = self
+            .concurrency
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Command queue closed");
+        if let Some(rate) = self.config.max_rate.filter(|rate| *rate > 0) {
+            let slot = {
+                let mut next_slot = self.next_slot.lock().await;
+                let slot = (*next_slot).max(Instant::now());
+                *next_slot = slot + Duration::from_secs(1) / rate;
+                slot
+            };
+            tokio::time::sleep_until(slot).await;
+        }
+
+        drop(waiting);
+        self.metrics.record_queue_latency(enqueued.elapsed());
+        QueuePermit {
+            _datapoint: None,
+            _concurrency: concurrency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_rate: Option<u32>) -> Arc<CommandQueue> {
+        let config = QueueConfig {
+            max_rate,
+            max_concurrency: 1,
+        };
+        Arc::new(CommandQueue::new(
+            config,
+            Arc::new(MetricsRecorder::default()),
+        ))
+    }
+
+    async fn wait_for_depth(queue: &CommandQueue, depth: u64) {
+        while queue.metrics.snapshot().queue_depth != depth {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    /// Queues two more writes to a data point behind a held one and returns whether each got sent.
+    async fn queue_behind(queue: Arc<CommandQueue>, coalesce: bool) -> (bool, bool) {
+        let first = queue.acquire("dp", coalesce).await.unwrap();
+        let second = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire("dp", coalesce).await.is_some() }
+        });
+        wait_for_depth(&queue, 1).await;
+        let third = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire("dp", coalesce).await.is_some() }
+        });
+        wait_for_depth(&queue, 2).await;
+        drop(first);
+        (second.await.unwrap(), third.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn overtaken_stateful_writes_are_coalesced() {
+        let queue = queue(None);
+        assert_eq!(queue_behind(queue.clone(), true).await, (false, true));
+
+        let metrics = queue.metrics.snapshot();
+        assert_eq!(metrics.coalesced_writes, 1);
+        assert_eq!(metrics.queue_depth, 0);
+    }
+
+    #[tokio::test]
+    async fn momentary_writes_are_never_coalesced() {
+        let queue = queue(None);
+        assert_eq!(queue_behind(queue.clone(), false).await, (true, true));
+        assert_eq!(queue.metrics.snapshot().coalesced_writes, 0);
+    }
+
+    #[tokio::test]
+    async fn writes_are_spaced_by_the_rate_limit() {
+        let queue = queue(Some(20));
+        let start = Instant::now();
+        for uid in ["a", "b", "c"] {
+            drop(queue.acquire(uid, true).await);
+        }
+        // The first write goes out at once, the other two 50ms apart.
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn abandoned_writes_leave_the_queue() {
+        let queue = queue(None);
+        let held = queue.acquire("dp", true).await.unwrap();
+        let waited =
+            tokio::time::timeout(Duration::from_millis(10), queue.acquire("dp", true)).await;
+        assert!(waited.is_err());
+        assert_eq!(queue.metrics.snapshot().queue_depth, 0);
+        drop(held);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How often and how patiently a request to the X1 is retried after a transient failure,
+/// i.e. a transport error or a 5xx response.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Fraction of each backoff (0.0 to 1.0) that is randomized.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retries.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// The delay before the given retry, starting at 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        backoff.mul_f64(1.0 - jitter * random_fraction())
+    }
+
+    /// Runs `request` until it succeeds, fails permanently or runs out of retries.
+    pub(crate) async fn run<T, F, Fut>(&self, mut request: F) -> Result<T, reqwest::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, reqwest::Error>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(e) if retry < self.max_retries && is_transient(&e) => {
+                    tracing::debug!("Retrying X1 request after error: {e}");
+                    tokio::time::sleep(self.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn is_transient(error: &reqwest::Error) -> bool {
+    match error.status() {
+        Some(status) => status.is_server_error(),
+        None => error.is_connect() || error.is_timeout() || error.is_request() || error.is_body(),
+    }
+}
+
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: f64) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            jitter,
+        }
+    }
+
+    fn status_error(status: u16) -> reqwest::Error {
+        let response = axum::http::Response::builder()
+            .status(status)
+            .body("")
+            .unwrap();
+        reqwest::Response::from(response)
+            .error_for_status()
+            .unwrap_err()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = policy(0.0);
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn jitter_only_shortens_the_backoff() {
+        let policy = policy(0.5);
+        for retry in 0..5 {
+            let full = RetryPolicy {
+                jitter: 0.0,
+                ..policy.clone()
+            }
+            .backoff(retry);
+            for _ in 0..100 {
+                let backoff = policy.backoff(retry);
+                assert!(backoff <= full);
+                assert!(backoff >= full / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn server_errors_are_transient_client_errors_are_not() {
+        assert!(is_transient(&status_error(500)));
+        assert!(is_transient(&status_error(503)));
+        assert!(!is_transient(&status_error(400)));
+        assert!(!is_transient(&status_error(401)));
+        assert!(!is_transient(&status_error(404)));
+    }
+
+    #[tokio::test]
+    async fn connection_errors_are_transient() {
+        let e = reqwest::Client::new()
+            .get("http://127.0.0.1:1/")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(is_transient(&e));
+    }
+
+    #[tokio::test]
+    async fn run_retries_transient_errors_only() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..policy(0.0)
+        };
+
+        let mut attempts = 0;
+        let result: Result<(), reqwest::Error> = policy
+            .run(|| {
+                attempts += 1;
+                async { Err(status_error(503)) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 4);
+
+        let mut attempts = 0;
+        let result: Result<(), reqwest::Error> = policy
+            .run(|| {
+                attempts += 1;
+                async { Err(status_error(404)) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result = RetryPolicy::none()
+            .run(|| {
+                attempts += 1;
+                async { Err::<(), _>(status_error(503)) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::x1::{X1, to_u16};
+
+/// Bit set on a KNX scene control value when the scene is taught instead of executed.
+const TEACH_BIT: u16 = 0x80;
+
+#[derive(Clone, Debug)]
+pub enum SceneType {
+    SET,
+    CONTROL,
+    UNKNOWN,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scene {
+    pub uid: String,
+    pub name: String,
+    pub scenetype: SceneType,
+    pub execute: Option<Execute>,
+    pub teach: Option<Teach>,
+    pub scenes: Vec<u16>,
+    pub location: Option<LocationId>,
+}
+
+impl Scene {
+    pub async fn execute(&self, x1: &X1, scene: u16) {
+        let execute_uid = self.execute.clone().expect("Error getting Execute").uid;
+        let _res = x1.set_value(execute_uid, scene).await;
+    }
+
+    /// Stores the current state of the scene's actuators as scene `scene`.
+    pub async fn teach(&self, x1: &X1, scene: u16) {
+        match self.scenetype {
+            SceneType::CONTROL => {
+                let execute_uid = self.execute.clone().expect("Error getting Execute").uid;
+                let _res = x1.set_value(execute_uid, scene | TEACH_BIT).await;
+            }
+            _ => {
+                let teach_uid = self.teach.clone().expect("Error getting Teach").uid;
+                let _res = x1.set_value(teach_uid, scene).await;
+            }
+        }
+    }
+
+    /// Decodes a value reported by the X1 into the invoked scene number and whether it was taught.
+    /// Returns `None` if the uid does not belong to this scene.
+    pub fn invocation(&self, uid: &str, value: &str) -> Option<(u16, bool)> {
+        let value = to_u16(value);
+        if self.execute.as_ref().is_some_and(|dp| dp.uid == uid) {
+            match self.scenetype {
+                SceneType::CONTROL => Some((value & !TEACH_BIT, value & TEACH_BIT != 0)),
+                _ => Some((value, false)),
+            }
+        } else if self.teach.as_ref().is_some_and(|dp| dp.uid == uid) {
+            Some((value, true))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Execute {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Teach {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scenes {
+    pub functions: X1Functions,
+}
+
+impl Scenes {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<Scene> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::SCENE(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Scene> {
+        match self.functions.get(uid).await? {
+            X1Function::SCENE(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+
+use crate::function::{FunctionKind, X1Function};
+use crate::locations::LocationId;
+use crate::x1::X1;
+
+/// What to look for with `X1::find` and `X1::search`.
+#[derive(Clone, Debug)]
+pub struct Query {
+    pub text: String,
+    pub location: Option<LocationId>,
+    pub kind: Option<FunctionKind>,
+}
+
+impl Query {
+    pub fn new(text: &str) -> Self {
+        Query {
+            text: text.to_string(),
+            location: None,
+            kind: None,
+        }
+    }
+
+    /// Only matches functions in the location or one of its sub-locations.
+    pub fn in_location(mut self, location: LocationId) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn of_kind(mut self, kind: FunctionKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+}
+
+/// How a candidate matched the query, from strongest to weakest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    UID,
+    ALIAS,
+    NAME,
+    CASEINSENSITIVE,
+    NORMALIZED,
+    PARTIAL,
+    FUZZY,
+}
+
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub function: X1Function,
+    pub matched: MatchKind,
+    /// Edit distance between the normalized query and name; only set for fuzzy matches.
+    pub distance: usize,
+}
+
+#[derive(Clone, Debug)]
+pub enum FindError {
+    NotFound(String),
+    Ambiguous(Vec<Candidate>),
+}
+
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FindError::NotFound(query) => write!(f, "no function matches \"{query}\""),
+            FindError::Ambiguous(candidates) => {
+                let names: Vec<&str> = candidates.iter().map(|c| c.function.name()).collect();
+                write!(f, "ambiguous match: {}", names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+/// Lowercases a name, strips accents and collapses everything but letters and digits
+/// into single spaces, so `Küche  (EG)` and `kuche eg` compare equal.
+pub fn normalize(text: &str) -> String {
+    let mut folded = String::new();
+    for c in text.to_lowercase().chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => folded.push('a'),
+            'ç' => folded.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => folded.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => folded.push('i'),
+            'ñ' => folded.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => folded.push('o'),
+            'ù' | 'ú' | 'û' | 'ü' => folded.push('u'),
+            'ý' | 'ÿ' => folded.push('y'),
+            'ß' => folded.push_str("ss"),
+            'æ' => folded.push_str("ae"),
+            'œ' => folded.push_str("oe"),
+            c if c.is_alphanumeric() => folded.push(c),
+            _ => folded.push(' '),
+        }
+    }
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Rates how well a function with the given uid, name and aliases matches the query text.
+fn rate(text: &str, uid: &str, name: &str, aliases: &[String]) -> Option<(MatchKind, usize)> {
+    let normalized = normalize(text);
+    let normalized_name = normalize(name);
+    if uid == text {
+        return Some((MatchKind::UID, 0));
+    }
+    if aliases.iter().any(|alias| normalize(alias) == normalized) {
+        return Some((MatchKind::ALIAS, 0));
+    }
+    if name == text {
+        return Some((MatchKind::NAME, 0));
+    }
+    if name.to_lowercase() == text.to_lowercase() {
+        return Some((MatchKind::CASEINSENSITIVE, 0));
+    }
+    if normalized_name == normalized {
+        return Some((MatchKind::NORMALIZED, 0));
+    }
+    if !normalized.is_empty()
+        && normalized
+            .split(' ')
+            .all(|word| normalized_name.split(' ').any(|w| w.starts_with(word)))
+    {
+        return Some((MatchKind::PARTIAL, 0));
+    }
+    let distance = edit_distance(&normalized, &normalized_name);
+    if distance <= 1.max(normalized.chars().count() / 4) {
+        return Some((MatchKind::FUZZY, distance));
+    }
+    None
+}
+
+impl X1 {
+    /// Registers an alternative name under which `find` and `search` match the function.
+    pub async fn add_alias(&self, alias: &str, uid: &str) {
+        self.aliases
+            .lock()
+            .await
+            .insert(normalize(alias), uid.to_string());
+    }
+
+    /// Returns all functions matching the query, best matches first.
+    pub async fn search(&self, query: &Query) -> Vec<Candidate> {
+        let in_location = match query.location {
+            Some(location) => Some(self.locations.functions(location, true).await),
+            None => None,
+        };
+        let aliases = self.aliases.lock().await.clone();
+
+        let mut candidates: Vec<Candidate> = self
+            .functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|function| query.kind.is_none_or(|kind| function.kind() == kind))
+            .filter(|function| {
+                in_location
+                    .as_ref()
+                    .is_none_or(|uids| uids.iter().any(|uid| uid == function.uid()))
+            })
+            .filter_map(|function| {
+                let function_aliases: Vec<String> = aliases
+                    .iter()
+                    .filter(|(_, uid)| *uid == function.uid())
+                    .map(|(alias, _)| alias.clone())
+                    .collect();
+                let (matched, distance) = rate(
+                    &query.text,
+                    function.uid(),
+                    function.name(),
+                    &function_aliases,
+                )?;
+                Some(Candidate {
+                    function,
+                    matched,
+                    distance,
+                })
+            })
+            .collect();
+        candidates.sort_by_key(|c| (c.matched, c.distance));
+        candidates
+    }
+
+    /// Returns the single best match for the query, or an error listing the candidates
+    /// if several functions match equally well.
+    pub async fn find(&self, query: &Query) -> Result<X1Function, FindError> {
+        pick(&query.text, self.search(query).await)
+    }
+}
+
+/// Picks the best of the candidates, sorted best first, unless several are equally good.
+fn pick(text: &str, candidates: Vec<Candidate>) -> Result<X1Function, FindError> {
+    let best = candidates
+        .first()
+        .ok_or_else(|| FindError::NotFound(text.to_string()))?;
+    let tied: Vec<Candidate> = candidates
+        .iter()
+        .filter(|c| c.matched == best.matched && c.distance == best.distance)
+        .cloned()
+        .collect();
+    if tied.len() > 1 {
+        return Err(FindError::Ambiguous(tied));
+    }
+    Ok(best.function.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::links::Link;
+
+    fn candidate(uid: &str, name: &str, matched: MatchKind, distance: usize) -> Candidate {
+        Candidate {
+            function: X1Function::LINK(Link {
+                uid: uid.to_string(),
+                name: name.to_string(),
+                url: None,
+                parameters: HashMap::new(),
+                location: None,
+            }),
+            matched,
+            distance,
+        }
+    }
+
+    fn kind(text: &str, name: &str) -> Option<MatchKind> {
+        rate(text, "a1b2", name, &[]).map(|(matched, _)| matched)
+    }
+
+    #[test]
+    fn normalize_folds_accents_case_and_punctuation() {
+        assert_eq!(normalize("Küche  (EG)"), "kuche eg");
+        assert_eq!(normalize("Küche"), normalize("kuche"));
+        assert_eq!(normalize("Straße"), "strasse");
+        assert_eq!(normalize("  "), "");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("kuche", "kuche"), 0);
+        assert_eq!(edit_distance("kuche", "kuchr"), 1);
+        assert_eq!(edit_distance("kuche", "kche"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn rate_ranks_matches_from_strongest_to_weakest() {
+        assert_eq!(
+            rate("a1b2", "a1b2", "Küche", &[]),
+            Some((MatchKind::UID, 0))
+        );
+        assert_eq!(
+            rate("cooking", "a1b2", "Küche", &["Cooking".to_string()]),
+            Some((MatchKind::ALIAS, 0))
+        );
+        assert_eq!(kind("Küche", "Küche"), Some(MatchKind::NAME));
+        assert_eq!(kind("küche", "Küche"), Some(MatchKind::CASEINSENSITIVE));
+        assert_eq!(kind("kuche", "Küche"), Some(MatchKind::NORMALIZED));
+        assert_eq!(kind("kuc deck", "Küche Decke"), Some(MatchKind::PARTIAL));
+        assert_eq!(
+            rate("kuchr", "a1b2", "Küche", &[]),
+            Some((MatchKind::FUZZY, 1))
+        );
+
+        assert!(MatchKind::UID < MatchKind::ALIAS);
+        assert!(MatchKind::NORMALIZED < MatchKind::PARTIAL);
+        assert!(MatchKind::PARTIAL < MatchKind::FUZZY);
+    }
+
+    #[test]
+    fn fuzzy_threshold_grows_with_the_query() {
+        // Up to 1 edit for short queries, a quarter of the length for longer ones.
+        assert_eq!(kind("kchn", "kuchen"), None);
+        assert_eq!(kind("wohnzimmr", "wohnzimmer"), Some(MatchKind::FUZZY));
+        assert_eq!(kind("wohnzimer", "wohnzimmer"), Some(MatchKind::FUZZY));
+        assert_eq!(kind("wonzimr", "wohnzimmer"), None);
+        assert_eq!(kind("garage", "kuche"), None);
+    }
+
+    #[test]
+    fn pick_returns_the_single_best_candidate() {
+        let candidates = vec![
+            candidate("1", "Küche", MatchKind::NORMALIZED, 0),
+            candidate("2", "Küche Decke", MatchKind::PARTIAL, 0),
+        ];
+        assert_eq!(pick("kuche", candidates).unwrap().uid(), "1");
+
+        let candidates = vec![
+            candidate("1", "Küche", MatchKind::FUZZY, 1),
+            candidate("2", "Kuchen", MatchKind::FUZZY, 2),
+        ];
+        assert_eq!(pick("kuchr", candidates).unwrap().uid(), "1");
+    }
+
+    #[test]
+    fn pick_reports_ties_as_ambiguous() {
+        let candidates = vec![
+            candidate("1", "Licht", MatchKind::NAME, 0),
+            candidate("2", "Licht", MatchKind::NAME, 0),
+            candidate("3", "Licht Flur", MatchKind::PARTIAL, 0),
+        ];
+        match pick("Licht", candidates) {
+            Err(FindError::Ambiguous(tied)) => {
+                let uids: Vec<&str> = tied.iter().map(|c| c.function.uid()).collect();
+                assert_eq!(uids, vec!["1", "2"]);
+            }
+            other => panic!("expected an ambiguous match, got {other:?}"),
+        }
+
+        assert!(matches!(pick("Licht", vec![]), Err(FindError::NotFound(_))));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::x1::to_u16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueType {
+    BINARY,
+    INTEGER,
+    FLOAT,
+    PERCENT,
+    TEXT,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Unit {
+    NONE,
+    CELSIUS,
+    PERCENT,
+    METERPERSECOND,
+    LUX,
+}
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::NONE => "",
+            Unit::CELSIUS => "°C",
+            Unit::PERCENT => "%",
+            Unit::METERPERSECOND => "m/s",
+            Unit::LUX => "lx",
+        }
+    }
+}
+
+/// Infers the value type of a status function from its channel type, falling back to the
+/// name of its data point (e.g. `Float` or `Binary`).
+pub(crate) fn infer_value_type(channel_type: &str, datapoint: &str) -> ValueType {
+    let kind = channel_type.rsplit('.').next().unwrap_or_default();
+    for name in [kind, datapoint] {
+        match name {
+            "Binary" => return ValueType::BINARY,
+            "Byte" | "Integer" | "DWord" => return ValueType::INTEGER,
+            "Float" | "Temperature" => return ValueType::FLOAT,
+            "Percent" => return ValueType::PERCENT,
+            "String" | "Text" => return ValueType::TEXT,
+            _ => (),
+        }
+    }
+    ValueType::TEXT
+}
+
+/// Infers the unit of a status function from its channel type, function type and data point name.
+pub(crate) fn infer_unit(channel_type: &str, function_type: &str, datapoint: &str) -> Unit {
+    let hints = [channel_type, function_type, datapoint]
+        .join(" ")
+        .to_lowercase();
+    if hints.contains("temperature") {
+        Unit::CELSIUS
+    } else if hints.contains("percent") || hints.contains("humidity") {
+        Unit::PERCENT
+    } else if hints.contains("wind") {
+        Unit::METERPERSECOND
+    } else if hints.contains("lux") || hints.contains("brightness") {
+        Unit::LUX
+    } else {
+        Unit::NONE
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BinarySensor {
+    pub uid: String,
+    pub name: String,
+    pub valuetype: ValueType,
+    pub unit: Unit,
+    pub status: Option<BinaryStatus>,
+    pub location: Option<LocationId>,
+}
+
+impl BinarySensor {
+    /// Applies a value reported by the X1. Returns `false` if the uid does not belong to this sensor.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        match self.status.as_mut().filter(|dp| dp.uid == uid) {
+            Some(status) => {
+                status.val = to_u16(value) != 0;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NumericSensor {
+    pub uid: String,
+    pub name: String,
+    pub valuetype: ValueType,
+    pub unit: Unit,
+    pub status: Option<NumericStatus>,
+    pub location: Option<LocationId>,
+}
+
+impl NumericSensor {
+    /// Applies a value reported by the X1. Returns `false` if the uid does not belong to this sensor.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        match self.status.as_mut().filter(|dp| dp.uid == uid) {
+            Some(status) => {
+                status.val = value.parse().unwrap_or(status.val);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TextSensor {
+    pub uid: String,
+    pub name: String,
+    pub valuetype: ValueType,
+    pub unit: Unit,
+    pub status: Option<TextStatus>,
+    pub location: Option<LocationId>,
+}
+
+impl TextSensor {
+    /// Applies a value reported by the X1. Returns `false` if the uid does not belong to this sensor.
+    pub fn update(&mut self, uid: &str, value: &str) -> bool {
+        match self.status.as_mut().filter(|dp| dp.uid == uid) {
+            Some(status) => {
+                status.val = value.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BinaryStatus {
+    pub uid: String,
+    /// The name of the data point in the uiconfig, usually `Binary`.
+    pub name: String,
+    pub val: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct NumericStatus {
+    pub uid: String,
+    /// The name of the data point in the uiconfig, which names the kind of number,
+    /// e.g. `Float` or `Percent`.
+    pub name: String,
+    pub val: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct TextStatus {
+    pub uid: String,
+    /// The name of the data point in the uiconfig, usually `String`.
+    pub name: String,
+    pub val: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sensors {
+    pub functions: X1Functions,
+}
+
+impl Sensors {
+    pub async fn list(&self) -> Vec<String> {
+        self.functions
+            .get_all()
+            .await
+            .iter()
+            .filter(|function| {
+                matches!(
+                    function,
+                    X1Function::BINARYSENSOR(_)
+                        | X1Function::NUMERICSENSOR(_)
+                        | X1Function::TEXTSENSOR(_)
+                )
+            })
+            .map(|function| function.name().to_string())
+            .collect()
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        match self.functions.get(uid).await? {
+            X1Function::BINARYSENSOR(_)
+            | X1Function::NUMERICSENSOR(_)
+            | X1Function::TEXTSENSOR(_) => self.functions.handle(uid).await,
+            _ => None,
+        }
+    }
+
+    pub async fn get_binary(&self) -> Vec<BinarySensor> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::BINARYSENSOR(sensor) => Some(sensor),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get_numeric(&self) -> Vec<NumericSensor> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::NUMERICSENSOR(sensor) => Some(sensor),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get_text(&self) -> Vec<TextSensor> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::TEXTSENSOR(sensor) => Some(sensor),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get_binary_in(&self, location: LocationId) -> Vec<BinarySensor> {
+        self.get_binary()
+            .await
+            .into_iter()
+            .filter(|sensor| sensor.location == Some(location))
+            .collect()
+    }
+
+    pub async fn get_numeric_in(&self, location: LocationId) -> Vec<NumericSensor> {
+        self.get_numeric()
+            .await
+            .into_iter()
+            .filter(|sensor| sensor.location == Some(location))
+            .collect()
+    }
+
+    pub async fn get_text_in(&self, location: LocationId) -> Vec<TextSensor> {
+        self.get_text()
+            .await
+            .into_iter()
+            .filter(|sensor| sensor.location == Some(location))
+            .collect()
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Shared state that is only ever replaced as a whole. Readers get a consistent snapshot
+/// by cloning an `Arc`, so they never wait on a writer for more than that, never hold a
+/// lock across an `.await` and never see a half-built value.
+#[derive(Debug, Default)]
+pub struct Snapshot<T> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> Snapshot<T> {
+    pub fn new(value: T) -> Self {
+        Snapshot {
+            current: RwLock::new(Arc::new(value)),
+        }
+    }
+
+    pub fn load(&self) -> Arc<T> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn store(&self, value: T) {
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(value);
+    }
+}
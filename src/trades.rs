@@ -0,0 +1,51 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::snapshot::Snapshot;
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Trade {
+    pub displayName: String,
+    pub functions: Option<Vec<String>>,
+    pub tradeType: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Trades {
+    pub trades: Arc<Snapshot<Vec<Trade>>>,
+}
+
+impl Trades {
+    pub async fn list(&self) -> Vec<String> {
+        let mut list: Vec<String> = vec![];
+
+        for trade in self.trades.load().iter() {
+            list.push(trade.displayName.clone());
+        }
+        list
+    }
+
+    pub async fn get_all(&self) -> Vec<Trade> {
+        self.trades.load().as_ref().clone()
+    }
+
+    /// Looks up a trade by its trade type (e.g. `de.gira.schema.trades.Shading`)
+    /// or by its display name.
+    pub async fn get(&self, trade: &str) -> Option<Trade> {
+        self.trades
+            .load()
+            .iter()
+            .find(|t| t.tradeType == trade || t.displayName == trade)
+            .cloned()
+    }
+
+    /// Returns the uids of all functions in the given trade.
+    pub async fn functions(&self, trade: &str) -> Vec<String> {
+        self.get(trade)
+            .await
+            .and_then(|t| t.functions)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use crate::function::{FunctionHandle, X1Function, X1Functions};
+use crate::locations::LocationId;
+use crate::x1::{X1, to_u16};
+
+#[derive(Clone, Debug)]
+pub struct Trigger {
+    pub uid: String,
+    pub name: String,
+    pub trigger: Option<TriggerPoint>,
+    pub location: Option<LocationId>,
+}
+
+impl Trigger {
+    pub async fn press(&self, x1: &X1) {
+        let trigger_uid = self.trigger.clone().expect("Error getting Trigger").uid;
+        let _res = x1.set_value(trigger_uid, 1).await;
+    }
+
+    /// Returns the value reported by the X1 if the uid belongs to this trigger.
+    pub fn triggered(&self, uid: &str, value: &str) -> Option<u16> {
+        self.trigger
+            .as_ref()
+            .filter(|dp| dp.uid == uid)
+            .map(|_| to_u16(value))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PressAndHold {
+    pub uid: String,
+    pub name: String,
+    pub press: Option<Press>,
+    pub location: Option<LocationId>,
+}
+
+impl PressAndHold {
+    pub async fn press(&self, x1: &X1) {
+        let press_uid = self.press.clone().expect("Error getting Press").uid;
+        let _res = x1.set_value(press_uid, 1).await;
+    }
+
+    pub async fn release(&self, x1: &X1) {
+        let press_uid = self.press.clone().expect("Error getting Press").uid;
+        let _res = x1.set_value(press_uid, 0).await;
+    }
+
+    /// Presses the button, keeps it pressed for `duration` and releases it again.
+    pub async fn hold(&self, x1: &X1, duration: Duration) {
+        self.press(x1).await;
+        tokio::time::sleep(duration).await;
+        self.release(x1).await;
+    }
+
+    /// Returns whether the button was pressed (`true`) or released (`false`)
+    /// if the uid belongs to this button.
+    pub fn pressed(&self, uid: &str, value: &str) -> Option<bool> {
+        self.press
+            .as_ref()
+            .filter(|dp| dp.uid == uid)
+            .map(|_| to_u16(value) != 0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TriggerPoint {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Press {
+    pub uid: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Triggers {
+    pub functions: X1Functions,
+}
+
+impl Triggers {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<Trigger> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::TRIGGER(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<Trigger> {
+        match self.functions.get(uid).await? {
+            X1Function::TRIGGER(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PressAndHolds {
+    pub functions: X1Functions,
+}
+
+impl PressAndHolds {
+    pub async fn list(&self) -> Vec<String> {
+        self.get_all()
+            .await
+            .iter()
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    pub async fn get_all(&self) -> Vec<PressAndHold> {
+        self.functions
+            .get_all()
+            .await
+            .into_iter()
+            .filter_map(|function| match function {
+                X1Function::PRESSANDHOLD(function) => Some(function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, uid: &str) -> Option<PressAndHold> {
+        match self.functions.get(uid).await? {
+            X1Function::PRESSANDHOLD(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    pub async fn handle(&self, uid: &str) -> Option<FunctionHandle> {
+        self.get(uid).await?;
+        self.functions.handle(uid).await
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tokio::task::JoinHandle;
+
+use crate::x1::X1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// Recent probes failed, but not yet often enough to reconnect.
+    Degraded,
+    Reconnecting,
+    Offline,
+}
+
+#[derive(Clone, Debug)]
+pub struct WatchdogConfig {
+    pub interval: Duration,
+    /// How long a single probe may take before it counts as failed.
+    pub timeout: Duration,
+    /// Failed probes in a row after which the watchdog reconnects.
+    pub failures_before_reconnect: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        WatchdogConfig {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5),
+            failures_before_reconnect: 3,
+        }
+    }
+}
+
+impl X1 {
+    pub async fn connection_state(&self) -> ConnectionState {
+        *self.connection.lock().await
+    }
+
+    /// Starts probing the X1 in the background. State transitions are published as
+    /// `ConnectionChanged` events. Abort the handle to stop the watchdog.
+    pub fn start_watchdog(&self, config: WatchdogConfig) -> JoinHandle<()> {
+        let x1 = self.clone();
+        tokio::spawn(async move {
+            let mut failures = 0;
+            let mut ticker = tokio::time::interval(config.interval);
+            loop {
+                ticker.tick().await;
+                let reconnect = match x1.probe(config.timeout).await {
+                    Ok(()) => {
+                        failures = 0;
+                        x1.connection_state().await == ConnectionState::Offline
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        e.status() == Some(StatusCode::UNAUTHORIZED)
+                            || failures >= config.failures_before_reconnect
+                    }
+                };
+                if !reconnect {
+                    let state = if failures == 0 {
+                        ConnectionState::Connected
+                    } else {
+                        ConnectionState::Degraded
+                    };
+                    x1.set_connection_state(state).await;
+                    continue;
+                }
+
+                x1.set_connection_state(ConnectionState::Reconnecting).await;
+                match x1.reconnect().await {
+                    Ok(()) => {
+                        failures = 0;
+                        x1.set_connection_state(ConnectionState::Connected).await;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Reconnecting to the X1 failed: {e}");
+                        x1.set_connection_state(ConnectionState::Offline).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Checks that the X1 answers and still accepts our token.
+    async fn probe(&self, timeout: Duration) -> Result<(), reqwest::Error> {
+        let token = self.get_token().unwrap_or_default();
+        let addr = self.addr();
+        self.client()
+            .get(format!("https://{addr}/api/v2/uiconfig/uid?token={token}"))
+            .timeout(timeout)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Re-authenticates, registers the callbacks again and resyncs all values, as the X1
+    /// may have lost both while it was gone and value changes were missed. If no project
+    /// was loaded yet, this finishes with a full `connect`.
+    async fn reconnect(&self) -> Result<(), Arc<reqwest::Error>> {
+        let old_token = self.get_token();
+        self.authenticate().await?;
+        // Only now that the new token is in place is the old client removed, so nothing
+        // ever runs without a token.
+        if let Some(old_token) = old_token.filter(|old| Some(old) != self.get_token().as_ref())
+            && let Err(e) = self.unregister(&old_token).await
+        {
+            // The X1 may have forgotten the client already, e.g. after a reboot.
+            tracing::debug!("Unregistering the old client failed: {e}");
+        }
+        self.reregister_callbacks().await?;
+        if !self.functions.is_empty().await {
+            self.refresh_states().await?;
+        }
+        self.connect().await
+    }
+}
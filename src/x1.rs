@@ -1,15 +1,35 @@
+use crate::callback_listener::Event;
+use crate::cameras::*;
+use crate::climate::*;
 use crate::covers::*;
+use crate::events::X1Event;
 use crate::function::X1Function;
 use crate::function::X1Functions;
+use crate::generic::*;
 use crate::lights::*;
+use crate::links::*;
 use crate::locations::*;
+use crate::media::*;
+use crate::metrics::{Metrics, MetricsRecorder};
+use crate::queue::{CommandQueue, QueueConfig, WriteOutcome};
+use crate::retry::RetryPolicy;
+use crate::scenes::*;
+use crate::sensors::*;
+use crate::snapshot::Snapshot;
+use crate::trades::*;
+use crate::triggers::*;
+use crate::watchdog::ConnectionState;
 
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 use tokio::sync::Mutex;
+use tokio::sync::broadcast;
 
 #[derive(Clone, Debug)]
 pub struct X1 {
@@ -17,14 +37,34 @@ pub struct X1 {
     user: String,
     password: String,
     client: reqwest::Client,
-    token: Arc<Mutex<Option<String>>>,
-    ui: Arc<Mutex<Option<UiResponse>>>,
+    token: Arc<Snapshot<Option<String>>>,
+    ui: Arc<Snapshot<Option<UiResponse>>>,
     pub lights: Lights,
     pub blinds: Blinds,
+    pub thermostats: Thermostats,
+    pub fan_coils: FanCoils,
+    pub scenes: Scenes,
+    pub triggers: Triggers,
+    pub press_and_holds: PressAndHolds,
+    pub sensors: Sensors,
+    pub audio_players: AudioPlayers,
+    pub cameras: Cameras,
+    pub links: Links,
     pub functions: X1Functions,
     pub locations: Locations,
-    last_location: Arc<Mutex<u16>>,
+    pub trades: Trades,
+    pub(crate) aliases: Arc<Mutex<HashMap<String, String>>>,
     pub connected: Arc<Mutex<bool>>,
+    pub(crate) connection: Arc<Mutex<ConnectionState>>,
+    callbacks: Arc<Mutex<Option<Callbacks>>>,
+    connecting: Arc<Mutex<Option<ConnectAttempt>>>,
+    events: broadcast::Sender<X1Event>,
+    reports: broadcast::Sender<(String, String)>,
+    idempotent: bool,
+    pub(crate) metrics: Arc<MetricsRecorder>,
+    queue: Arc<CommandQueue>,
+    read_retry: RetryPolicy,
+    write_retry: RetryPolicy,
 }
 
 impl X1 {
@@ -33,42 +73,145 @@ impl X1 {
             .danger_accept_invalid_certs(true)
             .build()
             .expect("Error creating client");
-        let mymutex: Mutex<Option<String>> = Mutex::new(None);
-        let myarc = Arc::new(mymutex);
-        let mymutex2: Mutex<Option<UiResponse>> = Mutex::new(None);
-        let myarc2 = Arc::new(mymutex2);
+        let (events, _) = broadcast::channel(64);
+        let (reports, _) = broadcast::channel(256);
+        let metrics = Arc::new(MetricsRecorder::default());
+        let functions = X1Functions::default();
 
         X1 {
             addr: addr.to_string(),
             user: user.to_string(),
             password: password.to_string(),
             client,
-            token: myarc,
-            ui: myarc2,
+            token: Arc::new(Snapshot::new(None)),
+            ui: Arc::new(Snapshot::new(None)),
             lights: Lights {
-                light: Arc::new(Mutex::new(vec![])),
+                functions: functions.clone(),
             },
             blinds: Blinds {
-                blinds: Arc::new(Mutex::new(vec![])),
+                functions: functions.clone(),
+            },
+            thermostats: Thermostats {
+                functions: functions.clone(),
+            },
+            fan_coils: FanCoils {
+                functions: functions.clone(),
+            },
+            scenes: Scenes {
+                functions: functions.clone(),
+            },
+            triggers: Triggers {
+                functions: functions.clone(),
+            },
+            press_and_holds: PressAndHolds {
+                functions: functions.clone(),
+            },
+            sensors: Sensors {
+                functions: functions.clone(),
+            },
+            audio_players: AudioPlayers {
+                functions: functions.clone(),
+            },
+            cameras: Cameras {
+                functions: functions.clone(),
+            },
+            links: Links {
+                functions: functions.clone(),
             },
             locations: Locations {
-                locations: Arc::new(Mutex::new(HashMap::new())),
-                set: Arc::new(Mutex::new(false)),
+                locations: Arc::new(Snapshot::new(HashMap::new())),
             },
-            last_location: Arc::new(Mutex::new(0)),
-            connected: Arc::new(Mutex::new(false)),
-            functions: X1Functions {
-                functions: Arc::new(Mutex::new(HashMap::new())),
+            trades: Trades {
+                trades: Arc::new(Snapshot::new(vec![])),
             },
+            aliases: Arc::new(Mutex::new(HashMap::new())),
+            connected: Arc::new(Mutex::new(false)),
+            connection: Arc::new(Mutex::new(ConnectionState::Offline)),
+            callbacks: Arc::new(Mutex::new(None)),
+            connecting: Arc::new(Mutex::new(None)),
+            functions,
+            events,
+            reports,
+            idempotent: false,
+            queue: Arc::new(CommandQueue::new(QueueConfig::default(), metrics.clone())),
+            metrics,
+            read_retry: RetryPolicy::default(),
+            write_retry: RetryPolicy::none(),
+        }
+    }
+
+    /// Returns a handle that skips writes which would not change the cached value of the
+    /// data point. The handle shares its connection and state with `self`.
+    pub fn idempotent(&self, enabled: bool) -> X1 {
+        X1 {
+            idempotent: enabled,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a handle that always writes, even if idempotent writes are enabled.
+    pub fn forced(&self) -> X1 {
+        self.idempotent(false)
+    }
+
+    /// Returns a handle whose writes go through a new command queue with the given limits.
+    /// Set this up before handing out clones, as those keep the queue they were made with.
+    pub fn with_queue(&self, config: QueueConfig) -> X1 {
+        X1 {
+            queue: Arc::new(CommandQueue::new(config, self.metrics.clone())),
+            ..self.clone()
         }
     }
 
-    pub async fn connect_x1(&self) {
-        if self.token.lock().await.is_some() {
+    /// Returns a handle that retries reads with the given policy. Reads are retried with
+    /// `RetryPolicy::default()` unless changed.
+    pub fn with_read_retry(&self, policy: RetryPolicy) -> X1 {
+        X1 {
+            read_retry: policy,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a handle that retries writes with the given policy. Writes are not retried
+    /// unless opted in, as a retried press or step may be applied twice.
+    pub fn with_write_retry(&self, policy: RetryPolicy) -> X1 {
+        X1 {
+            write_retry: policy,
+            ..self.clone()
+        }
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.snapshot()
+    }
+
+    /// Whether writing `value` can be skipped because the data point already has it.
+    pub(crate) async fn is_redundant(&self, uid: &str, value: &str) -> bool {
+        if !self.idempotent {
+            return false;
+        }
+        let redundant = self
+            .functions
+            .cached_value(uid)
+            .await
+            .is_some_and(|cached| same_value(&cached, value));
+        if redundant {
+            self.metrics.skipped_writes.fetch_add(1, Ordering::Relaxed);
+        }
+        redundant
+    }
+
+    pub async fn connect_x1(&self) -> Result<(), reqwest::Error> {
+        if self.get_token().is_some() {
             println!("Already connected. Skipping");
-            return;
+            return Ok(());
         }
 
+        self.authenticate().await
+    }
+
+    /// Registers this client with the X1 and stores the token it hands out.
+    pub(crate) async fn authenticate(&self) -> Result<(), reqwest::Error> {
         let body = "{\"client\":\"de.madone.x1client\"}";
         let addr = self.addr.clone();
         let token_json_str = self
@@ -77,109 +220,212 @@ impl X1 {
             .basic_auth(self.user.clone(), Some(self.password.clone()))
             .body(body)
             .send()
-            .await
-            .expect("Error sending post for auth")
+            .await?
+            .error_for_status()?
             .text()
-            .await
-            .expect("Error getting text int auth");
+            .await?;
         let token_hash: HashMap<String, String> =
-            serde_json::from_str(token_json_str.as_str()).expect("invalid json");
-        let token = token_hash
-            .get("token")
-            .expect("getting json from response failed");
-        let myarc = self.token.clone();
-        let mut mymutex = myarc.try_lock().expect("could not lock the mutex");
-        *mymutex = Some(token.to_owned());
+            serde_json::from_str(token_json_str.as_str()).unwrap_or_default();
+        if let Some(token) = token_hash.get("token") {
+            self.token.store(Some(token.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Removes the client registered under `token` from the X1, so re-authenticating does
+    /// not leave the old client behind.
+    pub(crate) async fn unregister(&self, token: &str) -> Result<(), reqwest::Error> {
+        let addr = self.addr.clone();
+        self.client
+            .delete(format!("https://{addr}/api/clients/{token}"))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Tells the X1 where to send value changes (and optionally service events) for this
+    /// client. The URLs are kept, so they are registered again after a reconnect.
+    pub async fn register_callbacks(
+        &self,
+        value_callback: &str,
+        service_callback: Option<&str>,
+    ) -> Result<(), reqwest::Error> {
+        *self.callbacks.lock().await = Some(Callbacks {
+            value_callback: value_callback.to_string(),
+            service_callback: service_callback.map(str::to_string),
+        });
+        self.reregister_callbacks().await
+    }
+
+    /// Registers the callbacks last passed to `register_callbacks` again, if any.
+    pub(crate) async fn reregister_callbacks(&self) -> Result<(), reqwest::Error> {
+        let Some(callbacks) = self.callbacks.lock().await.clone() else {
+            return Ok(());
+        };
+        let token = self.get_token().unwrap_or_default();
+        let addr = self.addr.clone();
+        let mut body = serde_json::json!({
+            "valueCallback": callbacks.value_callback,
+            "testCallbacks": false,
+        });
+        if let Some(service_callback) = callbacks.service_callback {
+            body["serviceCallback"] = serde_json::json!(service_callback);
+        }
+        self.client
+            .post(format!("https://{addr}/api/clients/{token}/callbacks"))
+            .body(body.to_string())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Subscribes to the events produced by `handle_event` and the refresh methods.
+    pub fn subscribe(&self) -> broadcast::Receiver<X1Event> {
+        self.events.subscribe()
+    }
+
+    pub(crate) fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    pub(crate) fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Records the state of the connection and publishes it if it changed. Going offline
+    /// clears `connected`; only a successful `connect` sets it again.
+    pub(crate) async fn set_connection_state(&self, state: ConnectionState) {
+        let mut connection = self.connection.lock().await;
+        if *connection == state {
+            return;
+        }
+        *connection = state;
+        if state == ConnectionState::Offline {
+            *self.connected.lock().await = false;
+        }
+        let _ = self.events.send(X1Event::ConnectionChanged { state });
+    }
+
+    /// Subscribes to every data point value reported to `apply_values`, changed or not.
+    pub(crate) fn subscribe_reports(&self) -> broadcast::Receiver<(String, String)> {
+        self.reports.subscribe()
     }
 
     pub fn get_token(&self) -> Option<String> {
-        let myarc = self.token.clone();
-        let mymutex = myarc.try_lock().expect("could not lock the mutex");
-        mymutex.clone()
+        self.token.load().as_ref().clone()
     }
 
-    pub async fn get_ui(&self) -> String {
-        if self.ui.clone().lock().await.clone().is_some() {
+    pub async fn get_ui(&self) -> Result<String, reqwest::Error> {
+        if self.ui.load().is_some() {
             println!("Already polled ui. Skipping");
-            return "".to_string();
+            return Ok("".to_string());
         }
 
-        let token = self
-            .get_token()
-            .expect("failed to get token. Not connected?");
+        self.load_ui().await?;
+        Ok(serde_json::to_string(self.ui.load().as_ref()).unwrap_or_default())
+    }
+
+    /// Fetches the uiconfig from the X1 and keeps it for creating devices and locations.
+    async fn load_ui(&self) -> Result<(), reqwest::Error> {
+        let ui = self.fetch_ui().await?;
+        self.ui.store(Some(ui));
+        Ok(())
+    }
+
+    /// Fetches the uiconfig from the X1. A uiconfig that cannot be parsed is a decode error.
+    async fn fetch_ui(&self) -> Result<UiResponse, reqwest::Error> {
+        let token = self.get_token().unwrap_or_default();
         let addr = self.addr.clone();
         let resp = self.client
         .get(
-            format!("https://{addr}/api/v2/uiconfig?expand=[dataPointFlasgs,parameters,locations,trades&token={token}"),
+            format!("https://{addr}/api/v2/uiconfig?expand=dataPointFlags,parameters,locations,trades&token={token}"),
         )
         .send()
-        .await
-        .expect("failed to get response of UI")
-        .text()
-        .await
-        .expect("failed to get text of UI response");
-        let myresp: UiResponse = serde_json::from_str(&resp).unwrap();
-        let myarc = self.ui.clone();
-        let mut mymutex = myarc.try_lock().expect("could not lock the mutex");
-        *mymutex = Some(myresp);
-        resp
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+        Ok(resp)
     }
 
     pub async fn get_value(&self, uid: String) -> Result<u16, reqwest::Error> {
-        let token = self
-            .get_token()
-            .expect("Error geting token. Not logged in?");
+        let token = self.get_token().unwrap_or_default();
         let addr = self.addr.clone();
-        if true {
-            let resp = self
-                .client
-                .get(format!("https://{addr}/api/v2/values/{uid}?token={token}"))
-                .send()
-                .await?
-                .text()
-                .await?;
-
-            let myresp: Value = serde_json::from_str(&resp).unwrap();
-            //println!("{:?}", myresp);
-            if let Some(val) = myresp.values {
-                let a = val[0].get("value").unwrap();
-                let a: Vec<&str> = a.split('.').collect();
-                let a = a[0].to_string();
-                let b: u16 = a.parse().unwrap();
-                return Ok(b);
-            }
+        let myresp = self
+            .read_retry
+            .run(|| async {
+                self.client
+                    .get(format!("https://{addr}/api/v2/values/{uid}?token={token}"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Value>()
+                    .await
+            })
+            .await?;
+        //println!("{:?}", myresp);
+        if let Some(a) = myresp
+            .values
+            .as_ref()
+            .and_then(|val| val.first())
+            .and_then(|val| val.get("value"))
+        {
+            return Ok(to_u16(a));
         }
         Ok(0)
     }
     pub async fn get_fn_values(&self, uid: String) -> Result<HashMap<String, u16>, reqwest::Error> {
-        let token = self
-            .get_token()
-            .expect("Error geting token. Not logged in?");
+        let values = self.get_fn_raw_values(uid).await?;
+        Ok(values
+            .iter()
+            .map(|(uid, value)| (uid.clone(), to_u16(value)))
+            .collect())
+    }
+
+    /// Like `get_fn_values`, but keeps the values as sent by the X1 (e.g. `"21.5"`).
+    pub async fn get_fn_raw_values(
+        &self,
+        uid: String,
+    ) -> Result<HashMap<String, String>, reqwest::Error> {
+        let token = self.get_token().unwrap_or_default();
         let addr = self.addr.clone();
-        let mut values: HashMap<String, u16> = HashMap::new();
-        if true {
-            let resp = self
-                .client
-                .get(format!("https://{addr}/api/v2/values/{uid}?token={token}"))
-                .send()
-                .await?
-                .text()
-                .await?;
-
-            let myresp: Value = serde_json::from_str(&resp).unwrap();
-
-            for val in myresp.values.unwrap_or(vec![]) {
-                //let key = val.get("uid").unwrap();
-                let value: u16 = val.get("value").unwrap().parse().unwrap_or(0);
-                values.insert(val.get("uid").unwrap().to_owned(), value);
+        let mut values: HashMap<String, String> = HashMap::new();
+        let myresp = self
+            .read_retry
+            .run(|| async {
+                self.client
+                    .get(format!("https://{addr}/api/v2/values/{uid}?token={token}"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Value>()
+                    .await
+            })
+            .await?;
+
+        for val in myresp.values.unwrap_or(vec![]) {
+            let value = val.get("value").cloned().unwrap_or_default();
+            if let Some(uid) = val.get("uid") {
+                values.insert(uid.to_owned(), value);
             }
-            //println!("{:?}", values);
         }
         Ok(values)
     }
 
-    pub async fn set_value(&self, uid: String, value: u16) -> Result<String, reqwest::Error> {
-        let token = self.get_token().expect("Error getting token");
+    pub async fn set_value(&self, uid: String, value: u16) -> Result<WriteOutcome, reqwest::Error> {
+        if self.is_redundant(&uid, &value.to_string()).await {
+            return Ok(WriteOutcome::Skipped);
+        }
+        let Some(_permit) = self
+            .queue
+            .acquire(&uid, self.functions.is_stateful(&uid).await)
+            .await
+        else {
+            return Ok(WriteOutcome::Coalesced);
+        };
+        let token = self.get_token().unwrap_or_default();
         let addr = self.addr.clone();
         let body = format!(
             "{{
@@ -192,31 +438,127 @@ impl X1 {
             }}"
         );
         let resp = self
-            .client
-            .put(format!("https://{addr}/api/v2/values?token={token}"))
-            //.basic_auth("MadOne", Some("5315herb"))
-            .body(body)
-            .send()
-            .await?
-            .text()
+            .write_retry
+            .run(|| async {
+                self.client
+                    .put(format!("https://{addr}/api/v2/values?token={token}"))
+                    //.basic_auth("MadOne", Some("5315herb"))
+                    .body(body.clone())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await
+            })
             .await?;
 
-        Ok(resp)
+        Ok(WriteOutcome::Sent(resp))
     }
 
-    pub async fn create_devices(&self) {
-        let light_count: usize = self.lights.light.lock().await.len();
-        let blind_count: usize = self.blinds.blinds.lock().await.len();
-        if light_count + blind_count != 0 {
+    /// Writes several data points in a single request.
+    pub async fn set_values(
+        &self,
+        values: Vec<(String, u16)>,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        let mut changed = vec![];
+        for (uid, value) in values {
+            if !self.is_redundant(&uid, &value.to_string()).await {
+                changed.push((uid, value));
+            }
+        }
+        if changed.is_empty() {
+            return Ok(WriteOutcome::Skipped);
+        }
+        let _permit = self.queue.acquire_batch().await;
+        let token = self.get_token().unwrap_or_default();
+        let addr = self.addr.clone();
+        let values: Vec<serde_json::Value> = changed
+            .into_iter()
+            .map(|(uid, value)| serde_json::json!({ "uid": uid, "value": value }))
+            .collect();
+        let body = serde_json::json!({ "values": values });
+        let resp = self
+            .write_retry
+            .run(|| async {
+                self.client
+                    .put(format!("https://{addr}/api/v2/values?token={token}"))
+                    .body(body.to_string())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await
+            })
+            .await?;
+
+        Ok(WriteOutcome::Sent(resp))
+    }
+
+    /// Like `set_value`, but for values that are not plain integers (e.g. temperatures).
+    pub async fn set_raw_value(
+        &self,
+        uid: String,
+        value: String,
+    ) -> Result<WriteOutcome, reqwest::Error> {
+        if self.is_redundant(&uid, &value).await {
+            return Ok(WriteOutcome::Skipped);
+        }
+        let Some(_permit) = self
+            .queue
+            .acquire(&uid, self.functions.is_stateful(&uid).await)
+            .await
+        else {
+            return Ok(WriteOutcome::Coalesced);
+        };
+        let token = self.get_token().unwrap_or_default();
+        let addr = self.addr.clone();
+        let body = serde_json::json!({ "values": [{ "uid": uid, "value": value }] });
+        let resp = self
+            .write_retry
+            .run(|| async {
+                self.client
+                    .put(format!("https://{addr}/api/v2/values?token={token}"))
+                    .body(body.to_string())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await
+            })
+            .await?;
+
+        Ok(WriteOutcome::Sent(resp))
+    }
+
+    pub async fn create_devices(&self) -> Result<(), reqwest::Error> {
+        if !self.functions.is_empty().await {
             println!("Already created devices. Skipping");
-            return;
+            return Ok(());
         }
-        let myarc = self.ui.clone();
-        let mymutex = myarc.try_lock().expect("could not lock the mutex");
-        let ui = mymutex.clone();
-        let uii = ui.expect("Error getting ui repsonse from mutex");
-        for function in uii.functions {
-            let values = self.get_fn_values(function.uid.clone()).await.unwrap();
+        let Some(uii) = self.ui.load().as_ref().clone() else {
+            tracing::warn!("No uiconfig to create devices from. Call get_ui first");
+            return Ok(());
+        };
+        let (devices, values) = self.build_devices(uii.functions).await?;
+        self.functions.replace(devices, &values).await;
+        Ok(())
+    }
+
+    /// Creates the devices of the given uiconfig functions with their current values. The
+    /// registry is left alone, so it can be swapped for the result in one go.
+    async fn build_devices(
+        &self,
+        functions: Vec<Function>,
+    ) -> Result<(HashMap<String, X1Function>, HashMap<String, String>), reqwest::Error> {
+        let mut devices: HashMap<String, X1Function> = HashMap::new();
+        let mut all_values: HashMap<String, String> = HashMap::new();
+        for function in functions {
+            let raw_values = self.get_fn_raw_values(function.uid.clone()).await?;
+            all_values.extend(raw_values.clone());
+            let values: HashMap<String, u16> = raw_values
+                .iter()
+                .map(|(uid, value)| (uid.clone(), to_u16(value)))
+                .collect();
             match function.channelType.as_str() {
                 "de.gira.schema.channels.Switch"
                 | "de.gira.schema.channels.DimmerWhite"
@@ -240,8 +582,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 myswitch_option = Some(myswitch)
                             }
@@ -250,8 +592,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 mydimm_option = Some(mydimm)
                             }
@@ -260,8 +602,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 mytuner_option = Some(mytuner)
                             }
@@ -278,12 +620,7 @@ impl X1 {
                         color: mycolor_option,
                         location: None,
                     };
-                    self.lights.light.lock().await.push(mylight.clone());
-                    self.functions
-                        .functions
-                        .lock()
-                        .await
-                        .insert(function.uid.clone(), X1Function::LIGHT(mylight));
+                    devices.insert(mylight.uid.clone(), X1Function::LIGHT(mylight));
                 }
 
                 "de.gira.schema.channels.BlindWithPos" => {
@@ -300,8 +637,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 mystepupdown_option = Some(mystepupdown)
                             }
@@ -310,8 +647,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 myupdown_option = Some(myupdown)
                             }
@@ -320,8 +657,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 myposition_option = Some(myposition)
                             }
@@ -330,8 +667,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 mymovement_option = Some(mymovement)
                             }
@@ -340,8 +677,8 @@ impl X1 {
                                     uid: function.dataPoints[pindex].uid.clone(),
                                     val: values
                                         .get(function.dataPoints[pindex].uid.as_str())
-                                        .unwrap()
-                                        .to_owned(),
+                                        .copied()
+                                        .unwrap_or_default(),
                                 };
                                 myslatpositon_option = Some(mymovement)
                             }
@@ -359,108 +696,663 @@ impl X1 {
                         slat_position: myslatpositon_option,
                         location: None,
                     };
-                    self.blinds.blinds.lock().await.push(myblind.clone());
-                    self.functions
-                        .functions
-                        .lock()
-                        .await
-                        .insert(function.uid.clone(), X1Function::BLIND(myblind));
+                    devices.insert(myblind.uid.clone(), X1Function::BLIND(myblind));
 
                     println!("Added blind")
                 }
 
-                _ => (),
+                "de.gira.schema.channels.RoomTemperatureSwitchable"
+                | "de.gira.schema.channels.KNX.HeatingCoolingSwitchable" => {
+                    let thermostat_type = match function.channelType.as_str() {
+                        "de.gira.schema.channels.RoomTemperatureSwitchable" => {
+                            ThermostatType::ROOMTEMPERATURE
+                        }
+                        "de.gira.schema.channels.KNX.HeatingCoolingSwitchable" => {
+                            ThermostatType::HEATINGCOOLING
+                        }
+                        _ => ThermostatType::UNKNOWN,
+                    };
+                    let mut mythermostat = Thermostat {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        thermostattype: thermostat_type,
+                        current: None,
+                        set_point: None,
+                        on_off: None,
+                        mode: None,
+                        status: None,
+                        presence: None,
+                        heating: None,
+                        cooling: None,
+                        location: None,
+                    };
+
+                    for point in function.dataPoints.iter() {
+                        let uid = point.uid.clone();
+                        let raw = raw_values
+                            .get(point.uid.as_str())
+                            .cloned()
+                            .unwrap_or_default();
+                        let val = to_u16(&raw);
+                        match point.name.as_str() {
+                            "Current" => {
+                                let val = raw.parse().unwrap_or(0.0);
+                                mythermostat.current = Some(Current { uid, val })
+                            }
+                            "Set-Point" => {
+                                let val = raw.parse().unwrap_or(0.0);
+                                mythermostat.set_point = Some(SetPoint { uid, val })
+                            }
+                            "OnOff" => mythermostat.on_off = Some(OnOff { uid, val }),
+                            "Mode" => mythermostat.mode = Some(Mode { uid, val }),
+                            "Status" => mythermostat.status = Some(Status { uid, val }),
+                            "Presence" => mythermostat.presence = Some(Presence { uid, val }),
+                            "Heating" => mythermostat.heating = Some(Heating { uid, val }),
+                            "Cooling" => mythermostat.cooling = Some(Cooling { uid, val }),
+                            _ => (),
+                        }
+                    }
+
+                    devices.insert(
+                        mythermostat.uid.clone(),
+                        X1Function::THERMOSTAT(mythermostat),
+                    );
+                }
+
+                "de.gira.schema.channels.KNX.FanCoil" => {
+                    let mut myfancoil = FanCoil {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        current: None,
+                        set_point: None,
+                        on_off: None,
+                        mode: None,
+                        fan_speed: None,
+                        location: None,
+                    };
+
+                    for point in function.dataPoints.iter() {
+                        let uid = point.uid.clone();
+                        let raw = raw_values
+                            .get(point.uid.as_str())
+                            .cloned()
+                            .unwrap_or_default();
+                        let val = to_u16(&raw);
+                        match point.name.as_str() {
+                            "Current" => {
+                                let val = raw.parse().unwrap_or(0.0);
+                                myfancoil.current = Some(Current { uid, val })
+                            }
+                            "Set-Point" => {
+                                let val = raw.parse().unwrap_or(0.0);
+                                myfancoil.set_point = Some(SetPoint { uid, val })
+                            }
+                            "OnOff" => myfancoil.on_off = Some(OnOff { uid, val }),
+                            "Mode" => myfancoil.mode = Some(Mode { uid, val }),
+                            "Fan-Speed" => myfancoil.fan_speed = Some(FanSpeed { uid, val }),
+                            _ => (),
+                        }
+                    }
+
+                    devices.insert(myfancoil.uid.clone(), X1Function::FANCOIL(myfancoil));
+                }
+
+                "de.gira.schema.channels.SceneSet" | "de.gira.schema.channels.SceneControl" => {
+                    let scene_type = match function.channelType.as_str() {
+                        "de.gira.schema.channels.SceneSet" => SceneType::SET,
+                        "de.gira.schema.channels.SceneControl" => SceneType::CONTROL,
+                        _ => SceneType::UNKNOWN,
+                    };
+                    let mut scenes: Vec<u16> = function
+                        .parameters
+                        .iter()
+                        .flatten()
+                        .filter(|p| p.key.to_lowercase().starts_with("scene"))
+                        .filter_map(|p| p.value.as_ref()?.parse().ok())
+                        .collect();
+                    scenes.sort();
+                    scenes.dedup();
+                    let mut myscene = Scene {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        scenetype: scene_type,
+                        execute: None,
+                        teach: None,
+                        scenes,
+                        location: None,
+                    };
+
+                    for point in function.dataPoints.iter() {
+                        let uid = point.uid.clone();
+                        match point.name.as_str() {
+                            "Execute" | "Scene" => myscene.execute = Some(Execute { uid }),
+                            "Teach" => myscene.teach = Some(Teach { uid }),
+                            _ => (),
+                        }
+                    }
+
+                    devices.insert(myscene.uid.clone(), X1Function::SCENE(myscene));
+                }
+
+                "de.gira.schema.channels.Trigger" => {
+                    let mut mytrigger = Trigger {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        trigger: None,
+                        location: None,
+                    };
+                    for point in function.dataPoints.iter() {
+                        if point.name == "Trigger" {
+                            mytrigger.trigger = Some(TriggerPoint {
+                                uid: point.uid.clone(),
+                            });
+                        }
+                    }
+
+                    devices.insert(mytrigger.uid.clone(), X1Function::TRIGGER(mytrigger));
+                }
+
+                "de.gira.schema.channels.PressAndHold" => {
+                    let mut mypressandhold = PressAndHold {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        press: None,
+                        location: None,
+                    };
+                    for point in function.dataPoints.iter() {
+                        if point.name == "Press" {
+                            mypressandhold.press = Some(Press {
+                                uid: point.uid.clone(),
+                            });
+                        }
+                    }
+
+                    devices.insert(
+                        mypressandhold.uid.clone(),
+                        X1Function::PRESSANDHOLD(mypressandhold),
+                    );
+                }
+
+                "de.gira.schema.channels.Binary" => {
+                    let point = function.dataPoints.first();
+                    let datapoint = point.map(|p| p.name.as_str()).unwrap_or_default();
+                    let mysensor = BinarySensor {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        valuetype: infer_value_type(&function.channelType, datapoint),
+                        unit: infer_unit(&function.channelType, &function.functionType, datapoint),
+                        status: point.map(|p| BinaryStatus {
+                            uid: p.uid.clone(),
+                            name: p.name.clone(),
+                            val: values.get(p.uid.as_str()).is_some_and(|v| *v != 0),
+                        }),
+                        location: None,
+                    };
+
+                    devices.insert(mysensor.uid.clone(), X1Function::BINARYSENSOR(mysensor));
+                }
+
+                "de.gira.schema.channels.Byte"
+                | "de.gira.schema.channels.Integer"
+                | "de.gira.schema.channels.DWord"
+                | "de.gira.schema.channels.Float"
+                | "de.gira.schema.channels.Percent"
+                | "de.gira.schema.channels.Temperature" => {
+                    let point = function.dataPoints.first();
+                    let datapoint = point.map(|p| p.name.as_str()).unwrap_or_default();
+                    let mysensor = NumericSensor {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        valuetype: infer_value_type(&function.channelType, datapoint),
+                        unit: infer_unit(&function.channelType, &function.functionType, datapoint),
+                        status: point.map(|p| NumericStatus {
+                            uid: p.uid.clone(),
+                            name: p.name.clone(),
+                            val: raw_values
+                                .get(p.uid.as_str())
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(0.0),
+                        }),
+                        location: None,
+                    };
+
+                    devices.insert(mysensor.uid.clone(), X1Function::NUMERICSENSOR(mysensor));
+                }
+
+                "de.gira.schema.channels.String" => {
+                    let point = function.dataPoints.first();
+                    let datapoint = point.map(|p| p.name.as_str()).unwrap_or_default();
+                    let mysensor = TextSensor {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        valuetype: infer_value_type(&function.channelType, datapoint),
+                        unit: infer_unit(&function.channelType, &function.functionType, datapoint),
+                        status: point.map(|p| TextStatus {
+                            uid: p.uid.clone(),
+                            name: p.name.clone(),
+                            val: raw_values.get(p.uid.as_str()).cloned().unwrap_or_default(),
+                        }),
+                        location: None,
+                    };
+
+                    devices.insert(mysensor.uid.clone(), X1Function::TEXTSENSOR(mysensor));
+                }
+
+                "de.gira.schema.channels.AudioWithPlaylist"
+                | "de.gira.schema.channels.Sonos.Audio" => {
+                    let audio_type = match function.channelType.as_str() {
+                        "de.gira.schema.channels.AudioWithPlaylist" => AudioType::PLAYLIST,
+                        "de.gira.schema.channels.Sonos.Audio" => AudioType::SONOS,
+                        _ => AudioType::UNKNOWN,
+                    };
+                    let mut myplayer = AudioPlayer {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        audiotype: audio_type,
+                        play: None,
+                        volume: None,
+                        mute: None,
+                        previous: None,
+                        next: None,
+                        shuffle: None,
+                        repeat: None,
+                        playlist: None,
+                        title: None,
+                        album: None,
+                        artist: None,
+                        location: None,
+                    };
+
+                    for point in function.dataPoints.iter() {
+                        let uid = point.uid.clone();
+                        let raw = raw_values
+                            .get(point.uid.as_str())
+                            .cloned()
+                            .unwrap_or_default();
+                        let val = to_u16(&raw);
+                        match point.name.as_str() {
+                            "Play" => myplayer.play = Some(Play { uid, val }),
+                            "Volume" => myplayer.volume = Some(Volume { uid, val }),
+                            "Mute" => myplayer.mute = Some(Mute { uid, val }),
+                            "Previous" => myplayer.previous = Some(Previous { uid }),
+                            "Next" => myplayer.next = Some(Next { uid }),
+                            "Shuffle" => myplayer.shuffle = Some(Shuffle { uid, val }),
+                            "Repeat" => myplayer.repeat = Some(Repeat { uid, val }),
+                            "Playlist" => myplayer.playlist = Some(Playlist { uid, val }),
+                            "Title" => myplayer.title = Some(Title { uid, val: raw }),
+                            "Album" => myplayer.album = Some(Album { uid, val: raw }),
+                            "Artist" => myplayer.artist = Some(Artist { uid, val: raw }),
+                            _ => (),
+                        }
+                    }
+
+                    devices.insert(myplayer.uid.clone(), X1Function::AUDIOPLAYER(myplayer));
+                }
+
+                "de.gira.schema.channels.Camera" => {
+                    let parameters = function.parameter_map();
+                    let mycamera = Camera {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        url: url_parameter(&parameters, false),
+                        snapshot_url: url_parameter(&parameters, true),
+                        parameters,
+                        location: None,
+                    };
+
+                    devices.insert(mycamera.uid.clone(), X1Function::CAMERA(mycamera));
+                }
+
+                "de.gira.schema.channels.Link" => {
+                    let parameters = function.parameter_map();
+                    let mylink = Link {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        url: url_parameter(&parameters, false),
+                        parameters,
+                        location: None,
+                    };
+
+                    devices.insert(mylink.uid.clone(), X1Function::LINK(mylink));
+                }
+
+                _ => {
+                    let mut datapoints: HashMap<String, GenericDataPoint> = HashMap::new();
+                    for point in function.dataPoints.iter() {
+                        let raw = raw_values
+                            .get(point.uid.as_str())
+                            .cloned()
+                            .unwrap_or_default();
+                        datapoints.insert(
+                            point.name.clone(),
+                            GenericDataPoint {
+                                uid: point.uid.clone(),
+                                val: DataValue::parse(&raw),
+                            },
+                        );
+                    }
+                    let mygeneric = GenericFunction {
+                        uid: function.uid.clone(),
+                        name: function.displayName.clone(),
+                        channel_type: function.channelType.clone(),
+                        function_type: function.functionType.clone(),
+                        datapoints,
+                        location: None,
+                    };
+
+                    devices.insert(mygeneric.uid.clone(), X1Function::GENERIC(mygeneric));
+                }
             }
         }
+        Ok((devices, all_values))
     }
 
-    pub fn create_locations(&self) -> UiLocation {
-        let mut root = UiLocation {
-            id: Some(0),
-            parent_location: None,
-            displayName: "Home".to_string(),
-            functions: Some(vec![]),
-            locationType: "root".to_string(),
-            locations: Some(vec![]),
-        };
-        root.locations = Some(
-            self.ui
-                .try_lock()
-                .expect("Error locking UI")
-                .clone()
-                .expect("Error getting UI")
-                .locations,
-        );
-        self.set_location_id(&mut root);
+    pub async fn create_locations(&self) -> UiLocation {
+        let (root, locations) = build_locations(self.ui.load().as_ref().as_ref());
+        self.publish_locations(locations).await;
 
         root
     }
 
-    pub fn set_location_id(&self, location: &mut UiLocation) {
-        let mut last_location_id = self
-            .last_location
-            .try_lock()
-            .expect("Error locking last_location");
-        *last_location_id += 1;
+    /// Assigns ids to a location and its sub-locations. `key` is the path of display names
+    /// leading to the location; siblings sharing a name are told apart by their order.
+    pub async fn set_location_id(&self, location: &mut UiLocation, key: &str) {
+        let mut locations = self.locations.locations.load().as_ref().clone();
+        assign_location_ids(location, key, &mut locations);
+        self.publish_locations(locations).await;
+    }
 
-        location.id = Some(*last_location_id);
-        drop(last_location_id);
+    /// Moves the functions into their locations and replaces the location snapshot.
+    async fn publish_locations(&self, locations: HashMap<LocationId, Location>) {
+        self.functions
+            .update(|functions| place_in_locations(functions, &locations))
+            .await;
+        self.locations.locations.store(locations);
+    }
 
-        if let Some(functions) = &location.functions {
-            for function in functions {
-                let mut mylights = self.lights.light.try_lock().expect("Error locking lights");
-                for light in mylights.iter_mut() {
-                    if *function == light.uid {
-                        light.location = location.id;
+    /// Returns the uids of the data points that report events, keyed by function uid.
+    /// Data points without flags in the uiconfig are assumed to report events.
+    pub(crate) async fn eventable_datapoints(&self) -> HashMap<String, Vec<String>> {
+        let ui = self.ui.load().as_ref().clone();
+        let mut datapoints: HashMap<String, Vec<String>> = HashMap::new();
+        for function in ui.map(|ui| ui.functions).unwrap_or_default() {
+            let uids: Vec<String> = function
+                .dataPoints
+                .iter()
+                .filter(|point| point.canEvent.unwrap_or(true))
+                .map(|point| point.uid.clone())
+                .collect();
+            if !uids.is_empty() {
+                datapoints.insert(function.uid.clone(), uids);
+            }
+        }
+        datapoints
+    }
+
+    /// Updates the cached devices with data point values (keyed by data point uid) and
+    /// publishes a `ValueChanged` event for every value that changed.
+    pub async fn apply_values(&self, values: &HashMap<String, String>) {
+        for (uid, value) in values {
+            let _ = self.reports.send((uid.clone(), value.clone()));
+        }
+        for event in self.functions.apply_changes(values).await {
+            let _ = self.events.send(event);
+        }
+    }
+
+    /// Re-reads the values of all devices from the X1, e.g. after callbacks were missed.
+    pub async fn refresh_states(&self) -> Result<(), reqwest::Error> {
+        for function in self.functions.get_all().await {
+            if function.as_readable().values().is_empty() {
+                continue;
+            }
+            let values = self.get_fn_raw_values(function.uid().to_string()).await?;
+            self.apply_values(&values).await;
+        }
+        Ok(())
+    }
+
+    /// Applies a value change reported through the callback listener to the cached devices
+    /// and publishes the resulting events to subscribers.
+    pub async fn handle_event(&self, event: &Event) {
+        self.apply_values(&HashMap::from([(event.uid.clone(), event.value.clone())]))
+            .await;
+
+        for function in self.functions.get_all().await {
+            match function {
+                X1Function::SCENE(scene) => {
+                    if let Some((number, teach)) = scene.invocation(&event.uid, &event.value) {
+                        let _ = self.events.send(X1Event::SceneInvoked {
+                            uid: scene.uid.clone(),
+                            name: scene.name.clone(),
+                            scene: number,
+                            teach,
+                        });
+                    }
+                }
+                X1Function::TRIGGER(trigger) => {
+                    if let Some(value) = trigger.triggered(&event.uid, &event.value) {
+                        let _ = self.events.send(X1Event::Triggered {
+                            uid: trigger.uid.clone(),
+                            name: trigger.name.clone(),
+                            value,
+                        });
                     }
                 }
-                let mut myblinds = self.blinds.blinds.try_lock().expect("Error locking lights");
-                for blind in myblinds.iter_mut() {
-                    if *function == blind.uid {
-                        blind.location = location.id;
+                X1Function::PRESSANDHOLD(press_and_hold) => {
+                    let uid = press_and_hold.uid.clone();
+                    let name = press_and_hold.name.clone();
+                    match press_and_hold.pressed(&event.uid, &event.value) {
+                        Some(true) => {
+                            let _ = self.events.send(X1Event::Pressed { uid, name });
+                        }
+                        Some(false) => {
+                            let _ = self.events.send(X1Event::Released { uid, name });
+                        }
+                        None => (),
                     }
                 }
+                _ => (),
             }
         }
+    }
+
+    pub async fn create_trades(&self) {
+        let Some(ui) = self.ui.load().as_ref().clone() else {
+            return;
+        };
+        self.trades.trades.store(ui.trades);
+    }
+
+    /// Returns all functions of the given trade, looked up by trade type or display name.
+    pub async fn functions_in_trade(&self, trade: &str) -> Vec<X1Function> {
+        let uids = self.trades.functions(trade).await;
+        let functions = self.functions.functions.load();
+        uids.iter()
+            .filter_map(|uid| functions.get(uid).cloned())
+            .collect()
+    }
 
-        if let Some(locations) = &mut location.locations {
-            for child_location in locations.iter_mut() {
-                child_location.parent_location = location.id;
-                self.set_location_id(child_location);
+    /// Returns all functions of the given trade in a location and its sub-locations,
+    /// e.g. all shading on the first floor.
+    pub async fn functions_in(&self, trade: &str, location: LocationId) -> Vec<X1Function> {
+        let in_location = self.locations.functions(location, true).await;
+
+        self.functions_in_trade(trade)
+            .await
+            .into_iter()
+            .filter(|function| in_location.iter().any(|uid| *uid == function.uid()))
+            .collect()
+    }
+
+    /// Returns the functions in a location and, if `recursive` is set, in its sub-locations.
+    pub async fn functions_in_location(
+        &self,
+        location: LocationId,
+        recursive: bool,
+    ) -> Vec<X1Function> {
+        let uids = self.locations.functions(location, recursive).await;
+        let functions = self.functions.functions.load();
+        uids.iter()
+            .filter_map(|uid| functions.get(uid).cloned())
+            .collect()
+    }
+
+    /// Connects to the X1 and loads its devices, trades and locations. Concurrent callers
+    /// share a single attempt and all get its result; after a failure the next call tries again.
+    pub async fn connect(&self) -> Result<(), Arc<reqwest::Error>> {
+        let attempt = {
+            let mut connecting = self.connecting.lock().await;
+            if *self.connected.lock().await {
+                return Ok(());
+            }
+            match connecting.as_ref() {
+                Some(attempt) => attempt.clone(),
+                None => {
+                    let x1 = self.clone();
+                    let attempt = async move { x1.establish().await.map_err(Arc::new) }
+                        .boxed()
+                        .shared();
+                    *connecting = Some(attempt.clone());
+                    attempt
+                }
             }
+        };
+
+        let result = attempt.clone().await;
+        let mut connecting = self.connecting.lock().await;
+        if connecting
+            .as_ref()
+            .is_some_and(|current| current.ptr_eq(&attempt))
+        {
+            *connecting = None;
         }
+        result
+    }
 
-        let mut location_ids: Vec<u16> = vec![];
-        for loc in location.locations.clone().unwrap_or(vec![]).iter() {
-            if let Some(id) = loc.id {
-                location_ids.push(id);
-            }
+    async fn establish(&self) -> Result<(), reqwest::Error> {
+        if self.get_token().is_none() {
+            self.authenticate().await?;
         }
-        let location_id_map = Location {
-            id: location.id,
-            parent_location: location.parent_location,
-            displayName: location.displayName.clone(),
-            functions: location.functions.clone(),
-            locationType: location.locationType.clone(),
-            locations: Some(location_ids),
-        };
-        self.locations
-            .locations
-            .try_lock()
-            .expect("error locking locations")
-            .insert(location.id.unwrap(), location_id_map);
-    }
-    pub async fn connect(&self) {
-        if *self.connected.lock().await == true {
-            return;
+        if self.ui.load().is_none() {
+            self.load_ui().await?;
         }
-        self.connect_x1().await;
-        self.get_ui().await;
-        self.create_devices().await;
-        self.create_locations();
+        self.create_devices().await?;
+        self.create_trades().await;
+        self.create_locations().await;
 
         *self.connected.lock().await = true;
+        self.set_connection_state(ConnectionState::Connected).await;
+        Ok(())
     }
+
+    /// Reloads the Gira project from the X1 and rebuilds devices, trades and locations.
+    /// Location ids stay the same for locations that were neither renamed nor moved.
+    /// Everything is built aside and swapped in at the end, so readers see either the old
+    /// or the new project; on failure the old project is kept.
+    pub async fn refresh(&self) -> Result<(), reqwest::Error> {
+        let ui = self.fetch_ui().await?;
+        let (mut devices, values) = self.build_devices(ui.functions.clone()).await?;
+        let (_, locations) = build_locations(Some(&ui));
+        place_in_locations(&mut devices, &locations);
+
+        self.functions.replace(devices, &values).await;
+        self.locations.locations.store(locations);
+        self.trades.trades.store(ui.trades.clone());
+        self.ui.store(Some(ui));
+        Ok(())
+    }
+}
+
+/// Builds the location tree of a uiconfig below a "Home" root, with ids assigned.
+fn build_locations(ui: Option<&UiResponse>) -> (UiLocation, HashMap<LocationId, Location>) {
+    let mut root = UiLocation {
+        id: None,
+        parent_location: None,
+        displayName: "Home".to_string(),
+        functions: Some(vec![]),
+        locationType: "root".to_string(),
+        locations: Some(vec![]),
+    };
+    root.locations = ui.map(|ui| ui.locations.clone());
+    let mut locations = HashMap::new();
+    assign_location_ids(&mut root, "", &mut locations);
+    (root, locations)
+}
+
+/// Sets the location of every function that is placed in one of the locations.
+fn place_in_locations(
+    functions: &mut HashMap<String, X1Function>,
+    locations: &HashMap<LocationId, Location>,
+) {
+    for location in locations.values() {
+        for uid in location.functions.iter().flatten() {
+            if let Some(function) = functions.get_mut(uid) {
+                function.set_location(location.id);
+            }
+        }
+    }
+}
+
+/// Assigns ids to a location and its sub-locations and collects them into `locations`.
+fn assign_location_ids(
+    location: &mut UiLocation,
+    key: &str,
+    locations: &mut HashMap<LocationId, Location>,
+) {
+    let mut id = location_id(key);
+    while locations.contains_key(&id) {
+        id = location_id(&format!("{key}#{id}"));
+    }
+    location.id = Some(id);
+
+    if let Some(children) = &mut location.locations {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for child_location in children.iter_mut() {
+            let count = seen.entry(child_location.displayName.clone()).or_insert(0);
+            *count += 1;
+            let child_key = match *count {
+                1 => format!("{key}/{}", child_location.displayName),
+                n => format!("{key}/{}#{n}", child_location.displayName),
+            };
+            child_location.parent_location = location.id;
+            assign_location_ids(child_location, &child_key, locations);
+        }
+    }
+
+    let mut location_ids: Vec<LocationId> = vec![];
+    for loc in location.locations.clone().unwrap_or(vec![]).iter() {
+        if let Some(id) = loc.id {
+            location_ids.push(id);
+        }
+    }
+    let location_id_map = Location {
+        id: location.id,
+        parent_location: location.parent_location,
+        displayName: location.displayName.clone(),
+        functions: location.functions.clone(),
+        locationType: location.locationType.clone(),
+        locations: Some(location_ids),
+    };
+    locations.insert(id, location_id_map);
+}
+
+/// Compares two values as sent by the X1, treating e.g. `"1"` and `"1.000000"` as equal.
+pub(crate) fn same_value(a: &str, b: &str) -> bool {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Converts a value as sent by the X1 (e.g. `"1"` or `"1.000000"`) into an integer.
+pub(crate) fn to_u16(value: &str) -> u16 {
+    value
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(0)
 }
 
 #[allow(non_snake_case)]
@@ -471,32 +1363,47 @@ struct Function {
     displayName: String,
     functionType: String,
     uid: String,
-    location: Option<u16>,
+    location: Option<LocationId>,
+    parameters: Option<Vec<Parameter>>,
+}
+impl Function {
+    /// Returns the uiconfig parameters of this function as a key/value map.
+    fn parameter_map(&self) -> HashMap<String, String> {
+        self.parameters
+            .iter()
+            .flatten()
+            .filter_map(|p| Some((p.key.clone(), p.value.clone()?)))
+            .collect()
+    }
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Parameter {
+    key: String,
+    set: Option<String>,
+    value: Option<String>,
 }
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct DataPoint {
     name: String,
     uid: String,
+    canRead: Option<bool>,
+    canWrite: Option<bool>,
+    canEvent: Option<bool>,
 }
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UiLocation {
-    id: Option<u16>,
-    parent_location: Option<u16>,
+    id: Option<LocationId>,
+    parent_location: Option<LocationId>,
     displayName: String,
     functions: Option<Vec<String>>,
     locationType: String,
     locations: Option<Vec<UiLocation>>,
 }
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Trade {
-    displayName: String,
-    functions: Option<Vec<String>>,
-    tradeType: String,
-}
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UiResponse {
@@ -504,9 +1411,109 @@ pub struct UiResponse {
     locations: Vec<UiLocation>,
     trades: Vec<Trade>,
 }
+/// A connection attempt that every concurrent caller of `X1::connect` awaits.
+type ConnectAttempt = Shared<BoxFuture<'static, Result<(), Arc<reqwest::Error>>>>;
+
+#[derive(Clone, Debug)]
+struct Callbacks {
+    value_callback: String,
+    service_callback: Option<String>,
+}
+
 #[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Value {
     pub values: Option<Vec<HashMap<String, String>>>,
     pub error: Option<HashMap<String, String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str, children: Vec<UiLocation>) -> UiLocation {
+        UiLocation {
+            id: None,
+            parent_location: None,
+            displayName: name.to_string(),
+            functions: Some(vec![]),
+            locationType: "room".to_string(),
+            locations: Some(children),
+        }
+    }
+
+    fn project() -> UiLocation {
+        location(
+            "Home",
+            vec![
+                location("Ground floor", vec![location("Kitchen", vec![])]),
+                location("Room", vec![]),
+                location("Room", vec![]),
+            ],
+        )
+    }
+
+    fn ids(location: &UiLocation) -> Vec<LocationId> {
+        let mut found = vec![location.id.unwrap()];
+        for child in location.locations.iter().flatten() {
+            found.extend(ids(child));
+        }
+        found
+    }
+
+    #[test]
+    fn ids_follow_the_path_of_names() {
+        let mut root = project();
+        let mut locations = HashMap::new();
+        assign_location_ids(&mut root, "", &mut locations);
+
+        let children = root.locations.as_ref().unwrap();
+        assert_eq!(root.id, Some(location_id("")));
+        assert_eq!(children[0].id, Some(location_id("/Ground floor")));
+        assert_eq!(
+            children[0].locations.as_ref().unwrap()[0].id,
+            Some(location_id("/Ground floor/Kitchen"))
+        );
+        assert_eq!(children[1].id, Some(location_id("/Room")));
+        assert_eq!(children[2].id, Some(location_id("/Room#2")));
+        assert_eq!(locations.len(), 5);
+    }
+
+    #[test]
+    fn rebuilding_gives_the_same_ids() {
+        let mut first = project();
+        let mut first_locations = HashMap::new();
+        assign_location_ids(&mut first, "", &mut first_locations);
+
+        let mut second = project();
+        let mut second_locations = HashMap::new();
+        assign_location_ids(&mut second, "", &mut second_locations);
+
+        assert_eq!(ids(&first), ids(&second));
+        let mut first_keys: Vec<_> = first_locations.keys().collect();
+        let mut second_keys: Vec<_> = second_locations.keys().collect();
+        first_keys.sort();
+        second_keys.sort();
+        assert_eq!(first_keys, second_keys);
+    }
+
+    #[test]
+    fn collisions_are_resolved_deterministically() {
+        let taken = location_id("/Room");
+        let mut locations = HashMap::new();
+        let mut other = location("Other", vec![]);
+        assign_location_ids(&mut other, "/Room", &mut locations);
+        assert_eq!(other.id, Some(taken));
+
+        let mut room = location("Room", vec![]);
+        assign_location_ids(&mut room, "/Room", &mut locations);
+        assert_eq!(room.id, Some(location_id(&format!("/Room#{taken}"))));
+
+        let mut again = HashMap::new();
+        let mut other = location("Other", vec![]);
+        assign_location_ids(&mut other, "/Room", &mut again);
+        let mut room_again = location("Room", vec![]);
+        assign_location_ids(&mut room_again, "/Room", &mut again);
+        assert_eq!(room.id, room_again.id);
+    }
+}
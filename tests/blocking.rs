@@ -0,0 +1,55 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use axum::{Router, routing::get, routing::post, routing::put};
+use gira_iot_api::blocking::X1;
+use gira_iot_api::function::X1Function;
+use gira_iot_api::queue::WriteOutcome;
+use tokio::runtime::Runtime;
+
+const PROJECT: &str = r#"{
+    "functions": [{
+        "channelType": "de.gira.schema.channels.Switch",
+        "dataPoints": [{ "name": "OnOff", "uid": "l1-onoff" }],
+        "displayName": "Kitchen",
+        "functionType": "de.gira.schema.functions.Switch",
+        "uid": "l1"
+    }],
+    "locations": [],
+    "trades": []
+}"#;
+
+/// A stand-in for the X1 with a single switched light, served from its own runtime so
+/// the blocking client can be driven from the test thread.
+fn x1(server: &Runtime) -> X1 {
+    let app = Router::new()
+        .route("/api/clients", post(|| async { r#"{"token":"t"}"# }))
+        .route("/api/v2/uiconfig", get(|| async { PROJECT }))
+        .route(
+            "/api/v2/values/{uid}",
+            get(|| async { r#"{"values":[{"uid":"l1-onoff","value":"0"}]}"# }),
+        )
+        .route("/api/v2/values", put(|| async {}));
+    let addr = server.block_on(common::serve_x1(app));
+    X1::new(&addr, "user", "password")
+}
+
+#[test]
+fn switching_a_light_updates_the_registry() {
+    let server = Runtime::new().unwrap();
+    let x1 = x1(&server);
+    x1.connect().unwrap();
+
+    let mut light = x1.lights().pop().expect("no lights");
+    assert_eq!(light.switch.as_ref().unwrap().val, 0);
+
+    let outcome = light.switch_on().unwrap();
+
+    assert!(matches!(outcome, WriteOutcome::Sent(_)));
+    assert_eq!(light.switch.as_ref().unwrap().val, 1);
+    match x1.function("l1") {
+        Some(X1Function::LIGHT(light)) => assert_eq!(light.switch.unwrap().val, 1),
+        other => panic!("expected the light, got {other:?}"),
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use axum::{Router, http::HeaderMap, http::StatusCode, routing::get};
+use gira_iot_api::cameras::{Camera, SnapshotError};
+use gira_iot_api::x1::X1;
+
+const IMAGE: &[u8] = b"\xff\xd8\xff\xe0fake-jpeg\xff\xd9";
+
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}")
+}
+
+fn camera(url: Option<String>, snapshot_url: Option<String>) -> Camera {
+    Camera {
+        uid: "cam1".to_string(),
+        name: "Front door".to_string(),
+        url,
+        snapshot_url,
+        parameters: HashMap::new(),
+        location: None,
+    }
+}
+
+#[tokio::test]
+async fn snapshot_prefers_snapshot_url() {
+    let app = Router::new()
+        .route("/snapshot.jpg", get(|| async { IMAGE }))
+        .route("/stream", get(|| async { StatusCode::NOT_FOUND }));
+    let base = serve(app).await;
+    let x1 = X1::new("127.0.0.1", "user", "password");
+
+    let cam = camera(
+        Some(format!("{base}/stream")),
+        Some(format!("{base}/snapshot.jpg")),
+    );
+
+    assert_eq!(cam.snapshot(&x1).await.unwrap(), IMAGE);
+}
+
+#[tokio::test]
+async fn snapshot_sends_credentials_from_parameters() {
+    let app = Router::new().route(
+        "/image",
+        get(|headers: HeaderMap| async move {
+            match headers.get("authorization") {
+                Some(_) => Ok(IMAGE),
+                None => Err(StatusCode::UNAUTHORIZED),
+            }
+        }),
+    );
+    let base = serve(app).await;
+    let x1 = X1::new("127.0.0.1", "user", "password");
+
+    let mut cam = camera(Some(format!("{base}/image")), None);
+    assert!(cam.snapshot(&x1).await.is_err());
+
+    cam.parameters
+        .insert("Username".to_string(), "viewer".to_string());
+    cam.parameters
+        .insert("Password".to_string(), "secret".to_string());
+    assert_eq!(cam.snapshot(&x1).await.unwrap(), IMAGE);
+}
+
+#[tokio::test]
+async fn snapshot_without_url_is_an_error() {
+    let x1 = X1::new("127.0.0.1", "user", "password");
+
+    let cam = camera(None, None);
+    assert!(matches!(cam.snapshot(&x1).await, Err(SnapshotError::NoUrl)));
+}
@@ -0,0 +1,23 @@
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+
+/// Serves `app` over HTTPS on a free local port, as a stand-in for the X1. Returns the
+/// address to pass to `X1::new`.
+pub async fn serve_x1(app: Router) -> String {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let config = RustlsConfig::from_pem(
+        cert.cert.pem().into_bytes(),
+        cert.key_pair.serialize_pem().into_bytes(),
+    )
+    .await
+    .unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum_server::from_tcp_rustls(listener, config)
+            .serve(app.into_make_service())
+            .await
+            .unwrap()
+    });
+    addr.to_string()
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::{Router, routing::get, routing::put};
+use gira_iot_api::callback_listener::Event;
+use gira_iot_api::confirm::{ConfirmOptions, Confirmation};
+use gira_iot_api::x1::X1;
+
+/// A stand-in for the X1 that accepts writes and reads back `reported`, or the last
+/// written value if `reported` is `None`.
+async fn x1(reported: Option<&'static str>) -> X1 {
+    let written = Arc::new(Mutex::new(String::new()));
+    let app = Router::new()
+        .route(
+            "/api/v2/values",
+            put(|State(written): State<Arc<Mutex<String>>>, body: String| async move {
+                let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+                *written.lock().unwrap() = body["values"][0]["value"].as_str().unwrap().to_string();
+            }),
+        )
+        .route(
+            "/api/v2/values/{uid}",
+            get(
+                move |Path(uid): Path<String>, State(written): State<Arc<Mutex<String>>>| async move {
+                    let value = reported
+                        .map(str::to_string)
+                        .unwrap_or_else(|| written.lock().unwrap().clone());
+                    serde_json::json!({ "values": [{ "uid": uid, "value": value }] }).to_string()
+                },
+            ),
+        )
+        .with_state(written);
+    X1::new(&common::serve_x1(app).await, "user", "password")
+}
+
+fn options(readback: bool) -> ConfirmOptions {
+    ConfirmOptions {
+        timeout: Duration::from_millis(500),
+        readback,
+        readback_interval: Duration::from_millis(50),
+    }
+}
+
+#[tokio::test]
+async fn readback_confirms_before_the_timeout() {
+    let x1 = x1(None).await;
+    let options = ConfirmOptions {
+        timeout: Duration::from_secs(10),
+        ..options(true)
+    };
+
+    let started = tokio::time::Instant::now();
+    let confirmation = x1
+        .set_value_confirmed("dp".to_string(), 1, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(confirmation, Confirmation::Confirmed);
+    assert!(started.elapsed() < options.timeout);
+}
+
+#[tokio::test]
+async fn reported_value_confirms() {
+    let x1 = x1(None).await;
+    let reporter = x1.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        reporter
+            .handle_event(&Event {
+                uid: "dp".to_string(),
+                value: "1".to_string(),
+            })
+            .await;
+    });
+
+    let confirmation = x1
+        .set_value_confirmed("dp".to_string(), 1, &options(false))
+        .await
+        .unwrap();
+
+    assert_eq!(confirmation, Confirmation::Confirmed);
+}
+
+#[tokio::test]
+async fn other_value_is_a_mismatch() {
+    let x1 = x1(Some("0")).await;
+
+    let confirmation = x1
+        .set_value_confirmed("dp".to_string(), 1, &options(true))
+        .await
+        .unwrap();
+
+    assert_eq!(confirmation, Confirmation::Mismatch("0".to_string()));
+}
+
+#[tokio::test]
+async fn no_report_times_out() {
+    let x1 = x1(None).await;
+
+    let started = tokio::time::Instant::now();
+    let confirmation = x1
+        .set_value_confirmed("dp".to_string(), 1, &options(false))
+        .await
+        .unwrap();
+
+    assert_eq!(confirmation, Confirmation::TimedOut);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
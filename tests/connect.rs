@@ -0,0 +1,69 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Router, routing::get, routing::post};
+use futures::future::join_all;
+use gira_iot_api::x1::X1;
+
+/// A stand-in for the X1 that counts client registrations and answers them slowly, so
+/// concurrent `connect` calls overlap.
+async fn x1(status: StatusCode, uiconfig: &'static str) -> (X1, Arc<AtomicUsize>) {
+    let registrations = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route(
+            "/api/clients",
+            post(
+                move |State(registrations): State<Arc<AtomicUsize>>| async move {
+                    registrations.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    (status, r#"{"token":"t"}"#)
+                },
+            ),
+        )
+        .route("/api/v2/uiconfig", get(move || async move { uiconfig }))
+        .with_state(registrations.clone());
+    let addr = common::serve_x1(app).await;
+    (X1::new(&addr, "user", "password"), registrations)
+}
+
+const EMPTY_PROJECT: &str = r#"{"functions":[],"locations":[],"trades":[]}"#;
+
+#[tokio::test]
+async fn concurrent_connects_share_one_attempt() {
+    let (x1, registrations) = x1(StatusCode::OK, EMPTY_PROJECT).await;
+
+    let results = join_all((0..5).map(|_| x1.connect())).await;
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(registrations.load(Ordering::SeqCst), 1);
+    assert!(*x1.connected.lock().await);
+}
+
+#[tokio::test]
+async fn concurrent_connects_share_the_error() {
+    let (x1, registrations) = x1(StatusCode::SERVICE_UNAVAILABLE, EMPTY_PROJECT).await;
+
+    let results = join_all((0..5).map(|_| x1.connect())).await;
+
+    let errors: Vec<_> = results.into_iter().map(Result::unwrap_err).collect();
+    assert!(errors.iter().all(|e| Arc::ptr_eq(e, &errors[0])));
+    assert_eq!(registrations.load(Ordering::SeqCst), 1);
+    assert!(!*x1.connected.lock().await);
+
+    // A failed attempt is not kept; the next call tries again.
+    assert!(x1.connect().await.is_err());
+    assert_eq!(registrations.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn malformed_uiconfig_is_an_error() {
+    let (x1, _) = x1(StatusCode::OK, "<html>maintenance</html>").await;
+
+    assert!(x1.connect().await.unwrap_err().is_decode());
+    assert!(!*x1.connected.lock().await);
+}
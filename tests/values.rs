@@ -0,0 +1,38 @@
+mod common;
+
+use axum::{Router, routing::get};
+use gira_iot_api::x1::X1;
+
+async fn x1(body: &'static str) -> X1 {
+    let app = Router::new().route("/api/v2/values/{uid}", get(move || async move { body }));
+    X1::new(&common::serve_x1(app).await, "user", "password")
+}
+
+#[tokio::test]
+async fn values_are_decoded() {
+    let x1 = x1(r#"{"values":[{"uid":"dp","value":"42"}]}"#).await;
+
+    assert_eq!(x1.get_value("dp".to_string()).await.unwrap(), 42);
+    assert_eq!(
+        x1.get_fn_raw_values("dp".to_string()).await.unwrap()["dp"],
+        "42"
+    );
+}
+
+#[tokio::test]
+async fn malformed_response_is_an_error() {
+    let x1 = x1("<html>maintenance</html>").await;
+
+    assert!(
+        x1.get_value("dp".to_string())
+            .await
+            .unwrap_err()
+            .is_decode()
+    );
+    assert!(
+        x1.get_fn_raw_values("dp".to_string())
+            .await
+            .unwrap_err()
+            .is_decode()
+    );
+}